@@ -1,24 +1,191 @@
+mod memoize;
+
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use syn::parse::Parser;
+use syn::{AttrStyle, ItemFn, LitStr};
 
+/// Times every call of the function and reports it to `measure_time_runtime`.
+///
+/// The timing is labelled with the function name unless a label is given:
+/// `#[measure_time(label = "parse")]`. Works on free functions and `impl` methods, generic and
+/// `async` ones included. The body is left as is, a guard created before it records the elapsed
+/// time when the function returns, so `return` and `?` behave exactly as without the attribute.
 #[proc_macro_attribute]
-pub fn measure_time(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
-    let fn_name = &input.sig.ident;
-    let fn_block = &input.block;
-    let fn_vis = &input.vis;
-    let fn_sig = &input.sig;
-
-    let result = quote! {
-        #fn_vis #fn_sig {
-            let start = std::time::Instant::now();
-            let result = (|| #fn_block)();
-            let duration = start.elapsed();
-            println!("Function '{}' executed in: {}.{:03}s", stringify!(#fn_name), duration.as_secs(), duration.subsec_millis());
-            result
+pub fn measure_time(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Caches the results of the function for the rest of the call tree, see
+/// `measure_time_runtime::memo`.
+///
+/// The cache is keyed by a tuple of clones of the arguments. Functions taking references or
+/// context that should not be part of the key give a reference to the key themselves:
+/// `#[memoize(key = design)]` for a `design: &str`, or `#[memoize(key = &(path, level))]`. The
+/// key is looked up by reference and turned into an owned key with `ToOwned` only when it is
+/// not cached yet, so a `&str` key costs no allocation on a hit. Owned keys must be
+/// `Hash + Eq + 'static` and results `Clone + 'static`. Hits and misses are counted per function
+/// for the runner to report.
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    memoize::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut label: Option<LitStr> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("label") {
+            label = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported measure_time argument, expected `label = \"...\"`"))
         }
-    };
+    });
+    attr_parser.parse2(attr)?;
+
+    let ItemFn { attrs, vis, sig, block } = syn::parse2(item.clone()).map_err(|_| {
+        syn::Error::new_spanned(&item, "`#[measure_time]` can only be applied to functions with a body")
+    })?;
+    let (inner_attrs, outer_attrs): (Vec<_>, Vec<_>) = attrs
+        .into_iter()
+        .partition(|attr| matches!(attr.style, AttrStyle::Inner(_)));
+    let fn_name = &sig.ident;
+    let label = label.unwrap_or_else(|| LitStr::new(&fn_name.to_string(), fn_name.span()));
+    let stmts = &block.stmts;
+
+    Ok(quote! {
+        #(#outer_attrs)*
+        #vis #sig {
+            #(#inner_attrs)*
+            let __measure_time_guard = ::measure_time_runtime::Guard::new(
+                #label,
+                concat!(module_path!(), "::", stringify!(#fn_name)),
+            );
+            #(#stmts)*
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::assert_eq;
+
+    fn guarded(label: &str, name: &str) -> TokenStream2 {
+        let name = syn::Ident::new(name, proc_macro2::Span::call_site());
+        quote! {
+            let __measure_time_guard = ::measure_time_runtime::Guard::new(
+                #label,
+                concat!(module_path!(), "::", stringify!(#name)),
+            );
+        }
+    }
+
+    fn assert_expands(attr: TokenStream2, item: TokenStream2, expected: TokenStream2) {
+        assert_eq!(expand(attr, item).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_keeps_body_and_returns() {
+        let guard = guarded("part_one", "part_one");
+        assert_expands(
+            quote!(),
+            quote! {
+                /// Doc comment.
+                pub fn part_one(input: &str) -> Result<usize, String> {
+                    let value = input.parse::<usize>().map_err(|error| error.to_string())?;
+                    if value == 0 {
+                        return Ok(1);
+                    }
+                    Ok(value)
+                }
+            },
+            quote! {
+                /// Doc comment.
+                pub fn part_one(input: &str) -> Result<usize, String> {
+                    #guard
+                    let value = input.parse::<usize>().map_err(|error| error.to_string())?;
+                    if value == 0 {
+                        return Ok(1);
+                    }
+                    Ok(value)
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_expand_keeps_inner_attributes_inside() {
+        let guard = guarded("part_two", "part_two");
+        assert_expands(
+            quote!(),
+            quote! {
+                #[inline]
+                fn part_two() -> u8 {
+                    #![allow(unused_mut)]
+                    let mut value = 2;
+                    value
+                }
+            },
+            quote! {
+                #[inline]
+                fn part_two() -> u8 {
+                    #![allow(unused_mut)]
+                    #guard
+                    let mut value = 2;
+                    value
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_expand_method_with_label() {
+        let guard = guarded("program", "run_program");
+        assert_expands(
+            quote!(label = "program"),
+            quote! {
+                pub fn run_program(&mut self) -> String {
+                    self.output()
+                }
+            },
+            quote! {
+                pub fn run_program(&mut self) -> String {
+                    #guard
+                    self.output()
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_expand_generic_async() {
+        let guard = guarded("load", "load");
+        assert_expands(
+            quote!(),
+            quote! {
+                async fn load<T: Default>(self) -> T where T: Clone {
+                    T::default()
+                }
+            },
+            quote! {
+                async fn load<T: Default>(self) -> T where T: Clone {
+                    #guard
+                    T::default()
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_expand_rejects_unknown_arguments() {
+        let error = expand(quote!(name = "x"), quote!(fn f() {})).unwrap_err();
 
-    result.into()
+        assert!(error.to_string().contains("unsupported measure_time argument"));
+    }
 }
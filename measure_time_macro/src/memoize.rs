@@ -0,0 +1,154 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::Parser;
+use syn::{AttrStyle, Expr, FnArg, ItemFn, Pat, ReturnType};
+
+pub fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut key: Option<Expr> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("key") {
+            key = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported memoize argument, expected `key = <expression>`"))
+        }
+    });
+    attr_parser.parse2(attr)?;
+
+    let ItemFn { attrs, vis, sig, block } = syn::parse2(item.clone()).map_err(|_| {
+        syn::Error::new_spanned(&item, "`#[memoize]` can only be applied to functions with a body")
+    })?;
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(asyncness, "`#[memoize]` cannot be applied to async functions"));
+    }
+    let key = match key {
+        Some(key) => quote!(#key),
+        None => default_key(&sig)?,
+    };
+
+    let (inner_attrs, outer_attrs): (Vec<_>, Vec<_>) = attrs
+        .into_iter()
+        .partition(|attr| matches!(attr.style, AttrStyle::Inner(_)));
+    let fn_name = &sig.ident;
+    let output = match &sig.output {
+        ReturnType::Default => quote!(-> ()),
+        ReturnType::Type(arrow, ty) => quote!(#arrow #ty),
+    };
+    let stmts = &block.stmts;
+
+    Ok(quote! {
+        #(#outer_attrs)*
+        #vis #sig {
+            #(#inner_attrs)*
+            ::measure_time_runtime::memo::cached(
+                stringify!(#fn_name),
+                concat!(module_path!(), "::", stringify!(#fn_name)),
+                #key,
+                || #output {
+                    #(#stmts)*
+                },
+            )
+        }
+    })
+}
+
+/// A reference to a tuple of clones of every argument, which only works for plain, owned
+/// arguments.
+fn default_key(sig: &syn::Signature) -> syn::Result<TokenStream2> {
+    let names = sig
+        .inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                receiver,
+                "`#[memoize]` on a method needs `key = <expression>`, `self` is not part of the key",
+            )),
+            FnArg::Typed(typed) => match &*typed.pat {
+                Pat::Ident(pat) => Ok(&pat.ident),
+                pat => Err(syn::Error::new_spanned(pat, "`#[memoize]` needs `key = <expression>` for pattern arguments")),
+            },
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote!(&(#(::std::clone::Clone::clone(&#names),)*)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memoize::*;
+    use std::assert_eq;
+
+    fn assert_expands(attr: TokenStream2, item: TokenStream2, expected: TokenStream2) {
+        assert_eq!(expand(attr, item).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_default_key() {
+        assert_expands(
+            quote!(),
+            quote! {
+                /// Doc comment.
+                fn count_stones(stone: u64, blinks: usize) -> usize {
+                    #![allow(unused_parens)]
+                    if blinks == 0 {
+                        return 1;
+                    }
+                    count_stones(stone * 2024, blinks - 1)
+                }
+            },
+            quote! {
+                /// Doc comment.
+                fn count_stones(stone: u64, blinks: usize) -> usize {
+                    #![allow(unused_parens)]
+                    ::measure_time_runtime::memo::cached(
+                        stringify!(count_stones),
+                        concat!(module_path!(), "::", stringify!(count_stones)),
+                        &(::std::clone::Clone::clone(&stone), ::std::clone::Clone::clone(&blinks),),
+                        || -> usize {
+                            if blinks == 0 {
+                                return 1;
+                            }
+                            count_stones(stone * 2024, blinks - 1)
+                        },
+                    )
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_expand_key_projection() {
+        assert_expands(
+            quote!(key = design),
+            quote! {
+                pub fn check(design: &str, towels: &[&str]) -> bool {
+                    towels.is_empty()
+                }
+            },
+            quote! {
+                pub fn check(design: &str, towels: &[&str]) -> bool {
+                    ::measure_time_runtime::memo::cached(
+                        stringify!(check),
+                        concat!(module_path!(), "::", stringify!(check)),
+                        design,
+                        || -> bool {
+                            towels.is_empty()
+                        },
+                    )
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_expand_rejects_what_has_no_default_key() {
+        let error = expand(quote!(), quote!(fn cost(&self, steps: usize) -> usize { steps })).unwrap_err();
+        assert!(error.to_string().contains("`self` is not part of the key"));
+
+        let error = expand(quote!(), quote!(fn cost((a, b): (u8, u8)) -> u8 { a + b })).unwrap_err();
+        assert!(error.to_string().contains("for pattern arguments"));
+
+        assert!(expand(quote!(key = &steps), quote!(fn cost(&self, steps: usize) -> usize { steps })).is_ok());
+        assert!(expand(quote!(scope = thread), quote!(fn f() {})).is_err());
+    }
+}
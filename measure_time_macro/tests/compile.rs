@@ -0,0 +1,6 @@
+#[test]
+fn test_compile() {
+    let tests = trybuild::TestCases::new();
+    tests.pass("tests/pass/*.rs");
+    tests.compile_fail("tests/ui/*.rs");
+}
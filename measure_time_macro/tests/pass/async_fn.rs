@@ -0,0 +1,35 @@
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use measure_time_macro::measure_time;
+
+#[measure_time(label = "load")]
+async fn load(value: u32) -> Result<u32, String> {
+    let doubled = double(value).await?;
+    Ok(doubled + 1)
+}
+
+async fn double(value: u32) -> Result<u32, String> {
+    value.checked_mul(2).ok_or_else(|| "overflow".to_string())
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let (result, timings) = measure_time_runtime::capture(|| {
+        let future = load(20);
+        block_on(future)
+    });
+
+    assert_eq!(result, Ok(41));
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].label, "load");
+}
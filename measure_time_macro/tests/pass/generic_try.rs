@@ -0,0 +1,32 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+use measure_time_macro::measure_time;
+
+#[measure_time]
+fn parse_all<T: FromStr>(input: &str) -> Result<Vec<T>, T::Err>
+where
+    T::Err: Debug,
+{
+    let mut values = Vec::new();
+    for token in input.split(',') {
+        values.push(token.trim().parse::<T>()?);
+    }
+    Ok(values)
+}
+
+#[measure_time]
+fn first<'a, T>(values: &'a [T]) -> Option<&'a T> {
+    let value = values.first()?;
+    Some(value)
+}
+
+fn main() {
+    let (_, timings) = measure_time_runtime::capture(|| {
+        assert_eq!(parse_all::<u32>("1, 2, 3"), Ok(vec![1, 2, 3]));
+        assert!(parse_all::<u32>("1, x").is_err());
+        assert_eq!(first::<u8>(&[]), None);
+        assert_eq!(first(&[7]), Some(&7));
+    });
+
+    assert_eq!(timings.len(), 4);
+}
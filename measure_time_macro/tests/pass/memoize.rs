@@ -0,0 +1,59 @@
+use measure_time_macro::{measure_time, memoize};
+
+#[memoize]
+fn count_paths(width: u64, height: u64) -> u64 {
+    if width == 0 || height == 0 {
+        return 1;
+    }
+    count_paths(width - 1, height) + count_paths(width, height - 1)
+}
+
+#[memoize(key = design)]
+#[measure_time]
+fn arrangements(design: &str, towels: &[&str]) -> usize {
+    if design.is_empty() {
+        return 1;
+    }
+    towels
+        .iter()
+        .filter_map(|towel| design.strip_prefix(towel))
+        .map(|rest| arrangements(rest, towels))
+        .sum()
+}
+
+struct Grid {
+    width: u64,
+}
+
+impl Grid {
+    #[memoize(key = &(self.width, row))]
+    fn cells(&self, row: u64) -> Result<u64, String> {
+        if row > 100 {
+            return Err(format!("row {} is out of range", row));
+        }
+        Ok(self.width * row)
+    }
+}
+
+fn main() {
+    measure_time_runtime::silence();
+    let ((paths, ways, cells), stats) = measure_time_runtime::memo::capture_stats(|| {
+        let ways = measure_time_runtime::memo::scope(|| {
+            let towels = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+            arrangements("gbbr", &towels) + arrangements("rrbgbr", &towels)
+        });
+        let grid = Grid { width: 3 };
+        let cells = measure_time_runtime::memo::scope(|| [grid.cells(2), grid.cells(2), grid.cells(200)]);
+        (count_paths(16, 16), ways, cells)
+    });
+
+    assert_eq!(paths, 601_080_390);
+    assert_eq!(ways, 10);
+    assert_eq!(cells, [Ok(6), Ok(6), Err("row 200 is out of range".to_string())]);
+
+    let labels: Vec<_> = stats.iter().map(|stats| stats.label).collect();
+    assert_eq!(labels, ["arrangements", "cells", "count_paths"]);
+    assert!(stats[0].hits > 0 && stats[2].hits > 0);
+    assert_eq!((stats[1].hits, stats[1].misses), (1, 2));
+    assert!(stats[2].function.ends_with("::count_paths"));
+}
@@ -0,0 +1,37 @@
+use measure_time_macro::measure_time;
+
+struct Computer {
+    program: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl Computer {
+    #[measure_time]
+    fn run_program(&mut self) -> String {
+        for &instruction in &self.program {
+            if instruction == 0 {
+                return "halted".to_string();
+            }
+            self.output.push(instruction * 2);
+        }
+        format!("{:?}", self.output)
+    }
+
+    #[measure_time(label = "into_output")]
+    fn into_output(self) -> Vec<u8> {
+        self.output
+    }
+}
+
+fn main() {
+    let (output, timings) = measure_time_runtime::capture(|| {
+        let mut computer = Computer { program: vec![1, 2, 0, 3], output: Vec::new() };
+        assert_eq!(computer.run_program(), "halted");
+        computer.into_output()
+    });
+
+    assert_eq!(output, vec![2, 4]);
+    let labels: Vec<_> = timings.iter().map(|timing| timing.label).collect();
+    assert_eq!(labels, ["run_program", "into_output"]);
+    assert!(timings[0].function.ends_with("::run_program"));
+}
@@ -0,0 +1,8 @@
+use measure_time_macro::memoize;
+
+#[memoize]
+async fn load(id: u32) -> u32 {
+    id
+}
+
+fn main() {}
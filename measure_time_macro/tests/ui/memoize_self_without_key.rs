@@ -0,0 +1,12 @@
+use measure_time_macro::memoize;
+
+struct Keypad;
+
+impl Keypad {
+    #[memoize]
+    fn cost(&self, presses: usize) -> usize {
+        presses
+    }
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use measure_time_macro::memoize;
+
+#[memoize(scope = "thread")]
+fn square(value: u64) -> u64 {
+    value * value
+}
+
+fn main() {}
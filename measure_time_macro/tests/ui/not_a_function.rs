@@ -0,0 +1,6 @@
+use measure_time_macro::measure_time;
+
+#[measure_time]
+struct Computer;
+
+fn main() {}
@@ -0,0 +1,8 @@
+use measure_time_macro::measure_time;
+
+trait Solver {
+    #[measure_time]
+    fn solve(&self) -> u64;
+}
+
+fn main() {}
@@ -0,0 +1,201 @@
+//! Where `#[measure_time]` sends its measurements.
+//!
+//! Every timed call ends up in [`record`]. While a [`capture`] is active on the current thread
+//! the timing is kept for the caller, otherwise it goes to the global collector, which prints to
+//! stdout until [`set_collector`] or [`silence`] replace it. The caches of `#[memoize]` live
+//! in [`memo`].
+
+pub mod memo;
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// One measured call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    /// The `label` given to the attribute, the function name otherwise.
+    pub label: &'static str,
+    /// Full path of the measured function, e.g. `aoc2024::day16::part_one`.
+    pub function: &'static str,
+    pub duration: Duration,
+}
+
+pub trait Collector: Send + Sync {
+    fn record(&self, timing: &Timing);
+}
+
+/// Prints every timing to stdout, the default.
+pub struct Stdout;
+
+/// Prints every timing to stderr, keeping stdout free for answers and reports.
+pub struct Stderr;
+
+/// Drops every timing.
+pub struct Silent;
+
+/// Keeps every timing in memory until it is taken.
+#[derive(Default)]
+pub struct Registry {
+    timings: Mutex<Vec<Timing>>,
+}
+
+impl Registry {
+    pub const fn new() -> Self {
+        Registry { timings: Mutex::new(Vec::new()) }
+    }
+
+    pub fn take(&self) -> Vec<Timing> {
+        std::mem::take(&mut *self.timings.lock().unwrap())
+    }
+}
+
+fn format_timing(timing: &Timing) -> String {
+    format!("Function '{}' executed in: {:?}", timing.label, timing.duration)
+}
+
+impl Collector for Stdout {
+    fn record(&self, timing: &Timing) {
+        let _ = writeln!(io::stdout(), "{}", format_timing(timing));
+    }
+}
+
+impl Collector for Stderr {
+    fn record(&self, timing: &Timing) {
+        let _ = writeln!(io::stderr(), "{}", format_timing(timing));
+    }
+}
+
+impl Collector for Silent {
+    fn record(&self, _timing: &Timing) {}
+}
+
+impl Collector for Registry {
+    fn record(&self, timing: &Timing) {
+        self.timings.lock().unwrap().push(timing.clone());
+    }
+}
+
+impl<C: Collector + ?Sized> Collector for &'static C {
+    fn record(&self, timing: &Timing) {
+        (**self).record(timing)
+    }
+}
+
+static COLLECTOR: RwLock<Option<Box<dyn Collector>>> = RwLock::new(None);
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<Timing>>> = const { RefCell::new(None) };
+}
+
+/// Replaces the global collector for every thread.
+pub fn set_collector(collector: impl Collector + 'static) {
+    *COLLECTOR.write().unwrap() = Some(Box::new(collector));
+}
+
+/// Drops every timing that is not captured.
+pub fn silence() {
+    set_collector(Silent);
+}
+
+/// Runs `f` and returns the timings recorded on this thread meanwhile, instead of passing them on
+/// to the global collector. Captures nest; the innermost one gets the timings.
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<Timing>) {
+    let outer = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let timings = CAPTURED.with(|captured| std::mem::replace(&mut *captured.borrow_mut(), outer));
+
+    (result, timings.unwrap_or_default())
+}
+
+/// Called by the code `#[measure_time]` generates.
+pub fn record(label: &'static str, function: &'static str, duration: Duration) {
+    let timing = Timing { label, function, duration };
+
+    let timing = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(timings) => {
+            timings.push(timing);
+            None
+        }
+        None => Some(timing),
+    });
+
+    if let Some(timing) = timing {
+        match COLLECTOR.read().unwrap().as_deref() {
+            Some(collector) => collector.record(&timing),
+            None => Stdout.record(&timing),
+        }
+    }
+}
+
+/// Records the time from its creation until it is dropped, which is how `#[measure_time]` times
+/// a function body without changing how it returns. Nothing is recorded while unwinding.
+pub struct Guard {
+    label: &'static str,
+    function: &'static str,
+    start: Instant,
+}
+
+impl Guard {
+    pub fn new(label: &'static str, function: &'static str) -> Self {
+        Guard { label, function, start: Instant::now() }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            record(self.label, self.function, self.start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_capture_collects_on_this_thread() {
+        let (value, timings) = capture(|| {
+            record("parse", "day01::parse", Duration::from_nanos(1500));
+            let (_, inner) = capture(|| record("inner", "day01::inner", Duration::from_nanos(1)));
+            assert_eq!(inner.len(), 1);
+            42
+        });
+
+        assert_eq!(value, 42);
+        assert_eq!(timings, vec![Timing { label: "parse", function: "day01::parse", duration: Duration::from_nanos(1500) }]);
+    }
+
+    #[test]
+    fn test_registry_collector() {
+        static REGISTRY: Registry = Registry::new();
+        set_collector(&REGISTRY);
+
+        std::thread::spawn(|| record("part_one", "day02::part_one", Duration::from_micros(3))).join().unwrap();
+
+        let timings = REGISTRY.take();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].label, "part_one");
+        assert!(REGISTRY.take().is_empty());
+    }
+
+    #[test]
+    fn test_guard_records_on_drop() {
+        let (_, timings) = capture(|| {
+            let _guard = Guard::new("solve", "day04::solve");
+        });
+
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].function, "day04::solve");
+    }
+
+    #[test]
+    fn test_format_timing_has_nanosecond_precision() {
+        let timing = Timing { label: "part_two", function: "day03::part_two", duration: Duration::from_nanos(1_234_567) };
+
+        assert_eq!(format_timing(&timing), "Function 'part_two' executed in: 1.234567ms");
+    }
+}
@@ -0,0 +1,232 @@
+//! The caches behind `#[memoize]`.
+//!
+//! Every memoized function gets a cache per thread, keyed by the projection of its arguments.
+//! Caches only live as long as the call tree they are filled in: when the outermost memoized
+//! call on a thread returns, every cache of that thread is cleared. That keeps functions whose
+//! key leaves out some context (the towels of day 19, say) correct from one call to the next.
+//! [`scope`] widens the tree to everything run inside it, for callers that know the left out
+//! context stays the same.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// How often one memoized function found its answer in the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoStats {
+    /// The name of the memoized function.
+    pub label: &'static str,
+    /// Full path of the memoized function, e.g. `aoc2024::day19::count_design_ways`.
+    pub function: &'static str,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl MemoStats {
+    /// The share of calls answered from the cache, from 0 to 1.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            calls => self.hits as f64 / calls as f64,
+        }
+    }
+}
+
+/// The cache of one memoized function, with the hits and misses not yet added to its
+/// [`MemoStats`].
+struct Cache {
+    function: &'static str,
+    /// Generic functions share a path across their instances, the types tell the caches apart.
+    type_id: TypeId,
+    label: &'static str,
+    hits: u64,
+    misses: u64,
+    values: Box<dyn Any>,
+}
+
+#[derive(Default)]
+struct State {
+    depth: usize,
+    /// One per function called in the current call tree; only a handful, so a list is the
+    /// quickest to search.
+    caches: Vec<Cache>,
+    stats: Vec<MemoStats>,
+}
+
+impl State {
+    /// Adds the hits and misses counted in the caches to the statistics.
+    fn flush_counts(&mut self) {
+        for cache in &mut self.caches {
+            if cache.hits + cache.misses == 0 {
+                continue;
+            }
+            let index = match self.stats.iter().position(|stats| stats.function == cache.function) {
+                Some(index) => index,
+                None => {
+                    self.stats.push(MemoStats { label: cache.label, function: cache.function, hits: 0, misses: 0 });
+                    self.stats.len() - 1
+                }
+            };
+            self.stats[index].hits += std::mem::take(&mut cache.hits);
+            self.stats[index].misses += std::mem::take(&mut cache.misses);
+        }
+    }
+
+    /// The index of the cache of `function` with keys `K` and values `V`, created if need be.
+    fn cache_index<K: 'static, V: 'static>(&mut self, label: &'static str, function: &'static str) -> usize {
+        let type_id = TypeId::of::<HashMap<K, V>>();
+        match self.caches.iter().position(|cache| cache.type_id == type_id && cache.function == function) {
+            Some(index) => index,
+            None => {
+                let values = Box::new(HashMap::<K, V>::new());
+                self.caches.push(Cache { function, type_id, label, hits: 0, misses: 0, values });
+                self.caches.len() - 1
+            }
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Marks one level of the call tree; the caches are cleared when the last one is dropped, also
+/// when unwinding.
+struct Level;
+
+impl Level {
+    fn enter() -> Level {
+        STATE.with(|state| state.borrow_mut().depth += 1);
+        Level
+    }
+}
+
+impl Drop for Level {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.depth -= 1;
+            if state.depth == 0 {
+                state.flush_counts();
+                state.caches.clear();
+            }
+        });
+    }
+}
+
+/// Runs `f` as one call tree, so the caches filled by the memoized calls inside it are shared
+/// until it returns.
+pub fn scope<T>(f: impl FnOnce() -> T) -> T {
+    let _level = Level::enter();
+    f()
+}
+
+/// Runs `f` and returns the statistics of the memoized calls made on this thread meanwhile.
+/// Captures nest; the innermost one gets the statistics.
+pub fn capture_stats<T>(f: impl FnOnce() -> T) -> (T, Vec<MemoStats>) {
+    let outer = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.flush_counts();
+        std::mem::take(&mut state.stats)
+    });
+    let result = f();
+    let stats = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.flush_counts();
+        std::mem::replace(&mut state.stats, outer)
+    });
+
+    (result, stats)
+}
+
+fn values<K: 'static, V: 'static>(cache: &mut Cache) -> &mut HashMap<K, V> {
+    cache.values.downcast_mut().expect("caches are told apart by their type")
+}
+
+/// Called by the code `#[memoize]` generates: the cached value for `key`, or the one `compute`
+/// returns, which is cached for the rest of the call tree.
+///
+/// The key is looked up by reference and only turned into an owned key on a miss, so a `&str`
+/// key allocates a `String` once per distinct key rather than once per call.
+pub fn cached<Q, V>(label: &'static str, function: &'static str, key: &Q, compute: impl FnOnce() -> V) -> V
+where
+    Q: Hash + Eq + ToOwned + ?Sized,
+    Q::Owned: Hash + Eq + 'static,
+    V: Clone + 'static,
+{
+    // A miss enters a level of the call tree right away, so the cache and its index stay put
+    // until the value is inserted.
+    let lookup = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let index = state.cache_index::<Q::Owned, V>(label, function);
+        let cache = &mut state.caches[index];
+        match values::<Q::Owned, V>(cache).get(key).cloned() {
+            Some(value) => {
+                cache.hits += 1;
+                Ok(value)
+            }
+            None => {
+                cache.misses += 1;
+                state.depth += 1;
+                Err(index)
+            }
+        }
+    });
+    let index = match lookup {
+        Ok(value) => return value,
+        Err(index) => index,
+    };
+
+    let _level = Level;
+    let value = compute();
+    STATE.with(|state| {
+        values::<Q::Owned, V>(&mut state.borrow_mut().caches[index]).insert(key.to_owned(), value.clone());
+    });
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memo::*;
+    use std::assert_eq;
+
+    fn fibonacci(n: u64) -> u64 {
+        cached("fibonacci", "tests::fibonacci", &n, || if n < 2 { n } else { fibonacci(n - 1) + fibonacci(n - 2) })
+    }
+
+    #[test]
+    fn test_cached_counts_hits_and_misses() {
+        let (value, stats) = capture_stats(|| fibonacci(30));
+
+        assert_eq!(value, 832_040);
+        assert_eq!(stats, vec![MemoStats { label: "fibonacci", function: "tests::fibonacci", hits: 28, misses: 31 }]);
+        assert!((stats[0].hit_rate() - 28.0 / 59.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_caches_live_for_one_call_tree() {
+        let offset = std::cell::Cell::new(0);
+        let shifted = |n: u64| cached("shifted", "tests::shifted", &n, || n + offset.get());
+
+        offset.set(1);
+        assert_eq!(shifted(1), 2);
+        offset.set(10);
+        assert_eq!(shifted(1), 11);
+
+        let (values, stats) = capture_stats(|| scope(|| [shifted(1), { offset.set(100); shifted(1) }]));
+        assert_eq!(values, [11, 11]);
+        assert_eq!((stats[0].hits, stats[0].misses), (1, 1));
+    }
+
+    #[test]
+    fn test_generic_instances_have_their_own_cache() {
+        fn describe<T: ToString + Hash + Eq + Clone + 'static>(value: T) -> String {
+            cached("describe", "tests::describe", &value, || value.to_string())
+        }
+
+        let (values, stats) = capture_stats(|| scope(|| (describe(1u8), describe("1"), describe(1u8))));
+        assert_eq!(values, ("1".to_string(), "1".to_string(), "1".to_string()));
+        assert_eq!((stats[0].hits, stats[0].misses), (1, 2));
+    }
+}
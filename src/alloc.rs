@@ -0,0 +1,191 @@
+//! Allocation counting for `--profile-alloc`.
+//!
+//! The binary installs [`CountingAllocator`] as its global allocator. It hands every request to
+//! the system allocator and, once [`enable`] was called, counts it for the thread making it.
+//! Every day runs on a thread of its own, so [`measure`] sees that day's allocations only, even
+//! when several days run at once.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The running totals of one thread. `live` can drop below zero when a thread frees memory
+/// another thread allocated.
+#[derive(Debug, Clone, Copy)]
+struct Counts {
+    allocations: u64,
+    bytes: u64,
+    live: i64,
+    peak: i64,
+}
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts { allocations: 0, bytes: 0, live: 0, peak: 0 }) };
+}
+
+fn record(allocated: usize, freed: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    // Fails only while the thread is torn down, those allocations are not worth counting.
+    let _ = COUNTS.try_with(|counts| {
+        let mut current = counts.get();
+        if allocated > 0 {
+            current.allocations += 1;
+            current.bytes += allocated as u64;
+        }
+        current.live += allocated as i64 - freed as i64;
+        current.peak = current.peak.max(current.live);
+        counts.set(current);
+    });
+}
+
+/// The system allocator, counting what it allocates once profiling is enabled.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            record(layout.size(), 0);
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            record(layout.size(), 0);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        record(0, layout.size());
+    }
+
+    /// Counts as an allocation of `new_size` bytes replacing the old block.
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            record(new_size, layout.size());
+        }
+        new_pointer
+    }
+}
+
+/// Starts counting allocations for the rest of the process.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// What a piece of code allocated on its thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, reallocations included.
+    pub allocations: u64,
+    /// Bytes requested over all allocations, whether freed since or not.
+    pub bytes: u64,
+    /// The most bytes it held at once, beyond what was allocated before it started.
+    pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+/// Runs `f` and returns what it allocated on this thread; all zeros unless profiling is enabled
+/// and the [`CountingAllocator`] is installed. Measurements nest.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let before = COUNTS.with(|counts| {
+        let before = counts.get();
+        counts.set(Counts { peak: before.live, ..before });
+        before
+    });
+    let result = f();
+    let stats = COUNTS.with(|counts| {
+        let after = counts.get();
+        counts.set(Counts { peak: before.peak.max(after.peak), ..after });
+        AllocStats {
+            allocations: after.allocations - before.allocations,
+            bytes: after.bytes - before.bytes,
+            peak: (after.peak - before.live).max(0) as u64,
+        }
+    });
+
+    (result, stats)
+}
+
+/// `bytes` in the largest binary unit that keeps it at least 1, e.g. `1.50 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use crate::alloc::*;
+    use std::assert_eq;
+    use std::hint::black_box;
+
+    #[test]
+    fn test_measure() {
+        enable();
+        let ((), stats) = measure(|| {
+            let mut numbers: Vec<u64> = Vec::with_capacity(16);
+            numbers.extend(0..16);
+            let copy = black_box(numbers.clone());
+            drop(black_box(numbers));
+            drop(copy);
+        });
+        assert_eq!(stats, AllocStats { allocations: 2, bytes: 256, peak: 256 });
+
+        let (kept, outer) = measure(|| {
+            let first = black_box(vec![0u8; 1000]);
+            let ((), inner) = measure(|| drop(black_box(vec![0u8; 500])));
+            assert_eq!(inner, AllocStats { allocations: 1, bytes: 500, peak: 500 });
+            first
+        });
+        assert_eq!(outer, AllocStats { allocations: 2, bytes: 1500, peak: 1500 });
+        drop(kept);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.00 MiB");
+        assert_eq!(AllocStats { allocations: 3, bytes: 2048, peak: 100 }.to_string(), "3 allocations, 2.00 KiB allocated, 100 B peak");
+    }
+}
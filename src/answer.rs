@@ -0,0 +1,120 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// The result of a single puzzle part.
+///
+/// Integers that fit into an `i64` are always stored as [`Answer::Integer`], so answers built
+/// through the `From` impls compare equal regardless of the type the day computed them in.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(i128),
+    Text(String),
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Text(_), _) | (_, Answer::Text(_)) => false,
+            (a, b) => a.as_i128() == b.as_i128(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Answer {
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(value) => Some(*value as i128),
+            Answer::BigInteger(value) => Some(*value),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigInteger(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+
+        Ok(match value.parse::<i128>() {
+            Ok(number) => Answer::from(number),
+            Err(_) => Answer::from(value),
+        })
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Integer(value),
+            Err(_) => Answer::BigInteger(value),
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(value: $integer) -> Self {
+                    Answer::from(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_integers_compare_across_types() {
+        assert_eq!(Answer::from(42usize), Answer::from(42i128));
+        assert_eq!(Answer::BigInteger(7), Answer::Integer(7));
+        assert_ne!(Answer::from(7), Answer::from("7"));
+    }
+
+    #[test]
+    fn test_big_integers_are_kept() {
+        assert_eq!(Answer::from(i128::MAX), Answer::BigInteger(i128::MAX));
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn test_parse_from_str() {
+        assert_eq!("  1234 ".parse::<Answer>().unwrap(), Answer::Integer(1234));
+        assert_eq!("170141183460469231731687303715884105727".parse::<Answer>().unwrap(), Answer::BigInteger(i128::MAX));
+        assert_eq!("co,de,ka,ta".parse::<Answer>().unwrap(), Answer::from("co,de,ka,ta"));
+    }
+}
@@ -0,0 +1,167 @@
+pub mod baseline;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use crate::solution::Part;
+
+/// How long a single measurement keeps sampling once the warmup is done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub budget: Budget,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { warmup: 1, budget: Budget::Time(Duration::from_secs(1)) }
+    }
+}
+
+/// Summary of the samples taken for one measurement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        let nanos: Vec<f64> = sorted.iter().map(|sample| sample.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        let variance = nanos.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / nanos.len() as f64;
+
+        Some(Stats {
+            samples: sorted.len(),
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        })
+    }
+}
+
+/// Timings of one day: parsing, every requested part on the parsed input, and the whole run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayBench {
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+    pub total: Stats,
+}
+
+/// Runs `f` `config.warmup` times untimed, then samples it until the budget is used up.
+///
+/// A time budget always takes at least one sample, so slow days are still measured.
+pub fn measure<T>(config: &BenchConfig, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(f());
+    }
+
+    let mut samples = Vec::new();
+    let start_time = Instant::now();
+    loop {
+        let sample_start = Instant::now();
+        black_box(f());
+        samples.push(sample_start.elapsed());
+
+        let done = match config.budget {
+            Budget::Iterations(iterations) => samples.len() >= iterations,
+            Budget::Time(budget) => start_time.elapsed() >= budget,
+        };
+        if done {
+            break;
+        }
+    }
+
+    Stats::from_samples(&samples).unwrap()
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos >= 1_000_000_000 {
+        format!("{:.2}s", nanos as f64 / 1_000_000_000.0)
+    } else if nanos >= 1_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1_000_000.0)
+    } else if nanos >= 1000 {
+        format!("{:.2}µs", nanos as f64 / 1000.0)
+    } else {
+        format!("{}ns", nanos)
+    }
+}
+
+/// Parses a budget duration such as `500ms`, `2s` or `1.5s`; a bare number is seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1.0)
+    } else {
+        (value, 1.0)
+    };
+
+    match number.parse::<f64>() {
+        Ok(number) if number.is_finite() && number > 0.0 => Ok(Duration::from_secs_f64(number * scale)),
+        _ => Err(format!("invalid duration `{}`, expected e.g. `500ms` or `2s`", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let samples: Vec<Duration> = [4, 1, 3, 2].iter().map(|&ms| Duration::from_millis(ms)).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev, Duration::from_nanos(1_118_034));
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_measure_iterations() {
+        let mut calls = 0;
+        let config = BenchConfig { warmup: 2, budget: Budget::Iterations(5) };
+        let stats = measure(&config, || calls += 1);
+
+        assert_eq!(stats.samples, 5);
+        assert_eq!(calls, 7);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("fast").is_err());
+    }
+}
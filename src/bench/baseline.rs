@@ -0,0 +1,288 @@
+//! Saved benchmark runs and the comparison of a new run against one.
+//!
+//! A baseline is a text file with one measurement per line: the day, what was measured
+//! (`parse`, `1`, `2` or `total`) and its statistics in nanoseconds. Baselines live in the
+//! `baselines` directory of the data directory, next to the inputs they were measured on.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::bench::{DayBench, Stats};
+use crate::solution::Part;
+
+const HEADER: &str = "# day measurement samples min_ns median_ns mean_ns stddev_ns";
+
+/// What one line of a benchmark measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measurement {
+    Parse,
+    Part(Part),
+    Total,
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Measurement::Parse => f.write_str("parse"),
+            Measurement::Part(part) => write!(f, "part {}", part),
+            Measurement::Total => f.write_str("total"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub day: u8,
+    pub measurement: Measurement,
+    pub stats: Stats,
+}
+
+/// The measurements of a benchmark run, in the order they were taken.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    pub entries: Vec<Entry>,
+}
+
+impl Baseline {
+    pub fn add_day(&mut self, day: u8, bench: &DayBench) {
+        let measurements = std::iter::once((Measurement::Parse, bench.parse))
+            .chain(bench.parts.iter().map(|&(part, stats)| (Measurement::Part(part), stats)))
+            .chain(std::iter::once((Measurement::Total, bench.total)));
+        self.entries.extend(measurements.map(|(measurement, stats)| Entry { day, measurement, stats }));
+    }
+
+    fn find(&self, day: u8, measurement: Measurement) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.day == day && entry.measurement == measurement)
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = format!("{}\n", HEADER);
+        for Entry { day, measurement, stats } in &self.entries {
+            let measurement = match measurement {
+                Measurement::Part(part) => part.to_string(),
+                other => other.to_string(),
+            };
+            text.push_str(&format!(
+                "{} {} {} {} {} {} {}\n",
+                day,
+                measurement,
+                stats.samples,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos()
+            ));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Baseline, String> {
+        let mut baseline = Baseline::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_entry(line).ok_or_else(|| format!("line {}: cannot read measurement `{}`", index + 1, line))?;
+            baseline.entries.push(entry);
+        }
+        Ok(baseline)
+    }
+
+    pub fn load(path: &Path) -> Result<Baseline, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("cannot read baseline {}: {}", path.display(), error))?;
+        Baseline::parse(&text).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, self.render())
+    }
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [day, measurement, samples, min, median, mean, stddev] = fields.as_slice() else {
+        return None;
+    };
+    let measurement = match *measurement {
+        "parse" => Measurement::Parse,
+        "1" => Measurement::Part(Part::One),
+        "2" => Measurement::Part(Part::Two),
+        "total" => Measurement::Total,
+        _ => return None,
+    };
+    let nanos = |field: &str| field.parse::<u64>().ok().map(Duration::from_nanos);
+
+    Some(Entry {
+        day: day.parse().ok()?,
+        measurement,
+        stats: Stats {
+            samples: samples.parse().ok()?,
+            min: nanos(min)?,
+            median: nanos(median)?,
+            mean: nanos(mean)?,
+            stddev: nanos(stddev)?,
+        },
+    })
+}
+
+/// Where the baseline called `name` is kept for the inputs in `data_dir`.
+pub fn path(data_dir: &Path, name: &str) -> PathBuf {
+    data_dir.join("baselines").join(format!("{}.txt", name))
+}
+
+/// Checks a baseline name, which becomes a file name.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) && !name.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid baseline name `{}`, expected letters, digits, `-`, `_` and `.`", name))
+    }
+}
+
+/// One measurement of a new run next to the same measurement in the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub day: u8,
+    pub measurement: Measurement,
+    pub before: Stats,
+    pub after: Stats,
+    /// Relative change of the mean, 0.1 being 10% slower.
+    pub change: f64,
+    /// Whether the change is more than the noise of the samples explains.
+    pub significant: bool,
+    /// Significantly slower by more than the threshold.
+    pub regression: bool,
+}
+
+/// Compares every measurement of `current` that `baseline` has too. `threshold` is the relative
+/// slowdown, e.g. 0.05, that counts as a regression when it is significant.
+///
+/// The threshold and the significance test both look at the mean. The median shrugs off
+/// outliers better, but without the samples there is no spread of the median to test against.
+pub fn compare(baseline: &Baseline, current: &Baseline, threshold: f64) -> Vec<Comparison> {
+    current.entries.iter().filter_map(|entry| {
+        let before = baseline.find(entry.day, entry.measurement)?.stats;
+        let after = entry.stats;
+        let change = match before.mean.as_nanos() {
+            0 => 0.0,
+            mean => (after.mean.as_nanos() as f64 - mean as f64) / mean as f64,
+        };
+        let significant = is_significant(&before, &after);
+
+        Some(Comparison {
+            day: entry.day,
+            measurement: entry.measurement,
+            before,
+            after,
+            change,
+            significant,
+            regression: significant && change > threshold,
+        })
+    }).collect()
+}
+
+/// Two sided critical values of Student's t at 95% for 1 to 30 degrees of freedom.
+const T_CRITICAL: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145, 2.131,
+    2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+/// Welch's t-test on the means at 95% confidence. Measurements with a single sample have no
+/// spread to test against, any difference between them counts.
+fn is_significant(before: &Stats, after: &Stats) -> bool {
+    let nanos = |duration: Duration| duration.as_nanos() as f64;
+    let (n1, n2) = (before.samples as f64, after.samples as f64);
+    let (v1, v2) = (nanos(before.stddev).powi(2) / n1, nanos(after.stddev).powi(2) / n2);
+    let difference = (nanos(after.mean) - nanos(before.mean)).abs();
+    if v1 + v2 == 0.0 {
+        return difference > 0.0;
+    }
+
+    let t = difference / (v1 + v2).sqrt();
+    let freedom = (v1 + v2).powi(2) / (v1.powi(2) / (n1 - 1.0).max(1.0) + v2.powi(2) / (n2 - 1.0).max(1.0));
+    let critical = match freedom.floor() as usize {
+        0 => T_CRITICAL[0],
+        df @ 1..=30 => T_CRITICAL[df - 1],
+        _ => 1.960,
+    };
+    t > critical
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::baseline::*;
+    use std::assert_eq;
+
+    fn stats(samples: usize, median_us: u64, stddev_us: u64) -> Stats {
+        let micros = Duration::from_micros;
+        Stats { samples, min: micros(median_us - stddev_us), median: micros(median_us), mean: micros(median_us), stddev: micros(stddev_us) }
+    }
+
+    fn with_mean(stats: Stats, mean_us: u64) -> Stats {
+        Stats { mean: Duration::from_micros(mean_us), ..stats }
+    }
+
+    fn run(part_one: Stats) -> Baseline {
+        let mut baseline = Baseline::default();
+        baseline.add_day(4, &DayBench { parse: stats(100, 20, 2), parts: vec![(Part::One, part_one)], total: stats(50, 1000, 10) });
+        baseline
+    }
+
+    #[test]
+    fn test_render_and_parse() {
+        let baseline = run(stats(80, 500, 25));
+        let text = baseline.render();
+
+        assert!(text.starts_with(HEADER));
+        assert!(text.contains("\n4 1 80 475000 500000 500000 25000\n"));
+        assert_eq!(Baseline::parse(&text), Ok(baseline));
+        assert_eq!(Baseline::parse("4 3 1 1 1 1 1").unwrap_err(), "line 1: cannot read measurement `4 3 1 1 1 1 1`");
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = run(stats(80, 500, 25));
+
+        let comparisons = compare(&baseline, &run(stats(80, 600, 25)), 0.05);
+        assert_eq!(comparisons.len(), 3);
+        let part_one = &comparisons[1];
+        assert_eq!(part_one.measurement, Measurement::Part(Part::One));
+        assert!((part_one.change - 0.2).abs() < 1e-9);
+        assert!(part_one.significant && part_one.regression);
+        assert!(!comparisons[0].significant && !comparisons[2].regression);
+
+        let noisy = compare(&baseline, &run(stats(5, 540, 200)), 0.05);
+        assert!(!noisy[1].significant && !noisy[1].regression);
+
+        let faster = compare(&baseline, &run(stats(80, 300, 25)), 0.05);
+        assert!(faster[1].significant && !faster[1].regression);
+    }
+
+    #[test]
+    fn test_compare_uses_the_mean() {
+        let baseline = run(stats(80, 500, 25));
+
+        let slow_outliers = compare(&baseline, &run(with_mean(stats(80, 500, 25), 600)), 0.05);
+        assert!((slow_outliers[1].change - 0.2).abs() < 1e-9);
+        assert!(slow_outliers[1].significant && slow_outliers[1].regression);
+
+        let slower_median = compare(&baseline, &run(with_mean(stats(80, 600, 25), 500)), 0.05);
+        assert_eq!(slower_median[1].change, 0.0);
+        assert!(!slower_median[1].significant && !slower_median[1].regression);
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert_eq!(validate_name("main-2024.12"), Ok(()));
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../main").is_err());
+        assert!(validate_name(".hidden").is_err());
+    }
+}
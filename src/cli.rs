@@ -0,0 +1,520 @@
+use std::collections::HashSet;
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+use aoc2024::bench::{self, baseline, BenchConfig, Budget};
+use aoc2024::input::{self, InputSource};
+use aoc2024::log::{Level, LogConfig};
+use aoc2024::pool;
+use aoc2024::render::{self, FrameConfig};
+use aoc2024::render::terminal::VisualizeConfig;
+use aoc2024::report::Format;
+use aoc2024::solution::Part;
+
+pub const USAGE: &str = "\
+Usage: aoc2024 [COMMAND] [DAYS...] [OPTIONS]
+
+Commands:
+  run       Solve the selected days and print their answers (default)
+  bench     Repeatedly time parsing and each part, reporting min/median/mean/stddev
+  verify    Compare answers with the dayNN.answers files stored next to the inputs
+  list      Show every registered day with the inputs and answers found for it
+
+Days are given as numbers, ranges and comma separated lists, e.g. `1-5,12 20`.
+When no days are given every registered day is selected.
+
+Options:
+  -p, --part <1|2>        Only solve the given part
+  -i, --input <PATH>      Read the input of the (single) selected day from PATH, `-` for stdin
+  -d, --data-dir <DIR>    Directory holding the dayNN.txt inputs
+                          [default: $AOC_DATA_DIR, else ./data]
+  -N, --input-name <NAME> Use the named inputs dayNN.NAME.txt, e.g. `example`
+  -j, --jobs <N|auto>     Run/verify: solve up to N days at once, output stays in day order
+                          [default: 1]
+  -T, --timeout <TIME>    Run/verify: give up on a day after TIME, e.g. 30s
+  -n, --iterations <N>    Bench: take exactly N samples per measurement
+  -b, --budget <TIME>     Bench: sample each measurement for TIME, e.g. 500ms [default: 1s]
+  -w, --warmup <N>        Bench: untimed runs before sampling [default: 1]
+      --save-baseline <NAME>
+                          Bench: save the run as baseline NAME in the data directory
+      --baseline <NAME>   Bench: compare with baseline NAME, failing on significant regressions
+      --threshold <PCT>   Slowdown of the mean counted as a regression, e.g. 10% [default: 5%]
+  -t, --timings <WHERE>   Where #[measure_time] reports go: stdout, stderr or off
+                          [default: stdout for run, off otherwise]
+  -f, --format <FORMAT>   Run/verify: write a json or csv report of every executed part
+  -o, --output <PATH>     Write the report to PATH instead of stdout
+      --frames <DIR>      Run: save the frames of simulating days (6, 14, 15) into DIR
+      --frame-format <F>  ppm or png for numbered frames, gif for an animation [default: png]
+      --scale <N>         Draw every cell of a frame as N x N pixels [default: 4]
+      --visualize         Run: animate the simulating days (6, 14, 15, 16) in the terminal
+      --fps <N>           Frames per second for --visualize, 0 for no limit [default: 30]
+      --profile-alloc     Run: count allocations, bytes and peak memory of parsing and each part
+  -v, --verbose           Log what the days find to stderr, -vv for every step they take
+      --log <SPEC>        Log levels per target, e.g. `day17=trace,day23=debug` or `info`
+                          (error, warn, info, debug, trace) [default: warn]
+  -h, --help              Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Run,
+    Bench,
+    Verify,
+    List,
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timings {
+    Stdout,
+    Stderr,
+    Off,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub days: Vec<u8>,
+    pub parts: Vec<Part>,
+    pub input: Option<PathBuf>,
+    pub data_dir: PathBuf,
+    pub input_name: Option<String>,
+    pub bench: BenchConfig,
+    pub timings: Timings,
+    pub report: Option<Format>,
+    pub report_output: Option<PathBuf>,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub frames: Option<FrameConfig>,
+    pub visualize: Option<VisualizeConfig>,
+    pub profile_alloc: bool,
+    pub log: LogConfig,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    /// Relative slowdown that counts as a regression against `baseline`, 0.05 for 5%.
+    pub threshold: f64,
+}
+
+impl Options {
+    /// Whether a report is written to stdout, which then carries nothing else.
+    pub fn report_to_stdout(&self) -> bool {
+        self.report.is_some() && self.report_output.is_none()
+    }
+
+    pub fn input_source(&self, day: u8) -> InputSource {
+        InputSource::resolve(day, self.input.as_deref(), &self.data_dir, self.input_name.as_deref())
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut args = args.into_iter().peekable();
+
+    let command = match args.peek().map(String::as_str) {
+        Some("run") => Some(Command::Run),
+        Some("bench") => Some(Command::Bench),
+        Some("verify") => Some(Command::Verify),
+        Some("list") => Some(Command::List),
+        Some("help") => Some(Command::Help),
+        _ => None,
+    };
+    let command = match command {
+        Some(command) => {
+            args.next();
+            command
+        }
+        None if args.peek().is_none() => Command::Help,
+        None => Command::Run,
+    };
+
+    let mut options = Options {
+        command,
+        days: Vec::new(),
+        parts: Part::BOTH.to_vec(),
+        input: None,
+        data_dir: PathBuf::new(),
+        input_name: None,
+        bench: BenchConfig::default(),
+        timings: Timings::Off,
+        report: None,
+        report_output: None,
+        jobs: 1,
+        timeout: None,
+        frames: None,
+        visualize: None,
+        profile_alloc: false,
+        log: LogConfig::default(),
+        save_baseline: None,
+        baseline: None,
+        threshold: 0.05,
+    };
+    let mut timings = None;
+    let mut data_dir = None;
+    let mut frame_format = None;
+    let mut frame_scale = None;
+    let mut fps = None;
+    let mut threshold = None;
+    let mut verbosity = 0;
+    let mut log_specs = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => options.command = Command::Help,
+            "-p" | "--part" => {
+                options.parts = match value_of(&arg, args.next())?.as_str() {
+                    "1" => vec![Part::One],
+                    "2" => vec![Part::Two],
+                    other => return Err(format!("invalid part `{}`, expected 1 or 2", other)),
+                };
+            }
+            "-i" | "--input" => options.input = Some(PathBuf::from(value_of(&arg, args.next())?)),
+            "-d" | "--data-dir" => data_dir = Some(PathBuf::from(value_of(&arg, args.next())?)),
+            "-N" | "--input-name" => options.input_name = Some(value_of(&arg, args.next())?),
+            "-j" | "--jobs" => {
+                options.jobs = match value_of(&arg, args.next())?.as_str() {
+                    "auto" => pool::available_workers(),
+                    jobs => parse_count(jobs, 1)?,
+                };
+            }
+            "-T" | "--timeout" => options.timeout = Some(bench::parse_duration(&value_of(&arg, args.next())?)?),
+            "-n" | "--iterations" => {
+                options.bench.budget = Budget::Iterations(parse_count(&value_of(&arg, args.next())?, 1)?);
+            }
+            "-b" | "--budget" => {
+                options.bench.budget = Budget::Time(bench::parse_duration(&value_of(&arg, args.next())?)?);
+            }
+            "-w" | "--warmup" => options.bench.warmup = parse_count(&value_of(&arg, args.next())?, 0)?,
+            "-t" | "--timings" => {
+                timings = Some(match value_of(&arg, args.next())?.as_str() {
+                    "stdout" => Timings::Stdout,
+                    "stderr" => Timings::Stderr,
+                    "off" => Timings::Off,
+                    other => return Err(format!("invalid timings output `{}`, expected stdout, stderr or off", other)),
+                });
+            }
+            "-f" | "--format" => options.report = Some(value_of(&arg, args.next())?.parse()?),
+            "-o" | "--output" => options.report_output = Some(PathBuf::from(value_of(&arg, args.next())?)),
+            "--frames" => {
+                options.frames = Some(FrameConfig {
+                    directory: PathBuf::from(value_of(&arg, args.next())?),
+                    format: render::Format::Png,
+                    scale: 4,
+                });
+            }
+            "--frame-format" => frame_format = Some(value_of(&arg, args.next())?.parse::<render::Format>()?),
+            "--scale" => frame_scale = Some(parse_count(&value_of(&arg, args.next())?, 1)?),
+            "--visualize" => options.visualize = Some(VisualizeConfig { fps: 30 }),
+            "--fps" => {
+                let value = value_of(&arg, args.next())?;
+                fps = Some(value.parse::<u32>().map_err(|_| format!("invalid frame rate `{}`, expected frames per second", value))?);
+            }
+            "--profile-alloc" => options.profile_alloc = true,
+            "--log" => log_specs.push(value_of(&arg, args.next())?),
+            "--verbose" => verbosity += 1,
+            flag if flag.len() > 1 && flag.strip_prefix('-').is_some_and(|vs| vs.chars().all(|c| c == 'v')) => {
+                verbosity += flag.len() - 1;
+            }
+            "--save-baseline" => options.save_baseline = Some(baseline_name(value_of(&arg, args.next())?)?),
+            "--baseline" => options.baseline = Some(baseline_name(value_of(&arg, args.next())?)?),
+            "--threshold" => threshold = Some(parse_percentage(&value_of(&arg, args.next())?)?),
+            flag if flag.starts_with('-') => {
+                return Err(format!("unknown option `{}`", flag));
+            }
+            spec => options.days.extend(parse_days(spec)?),
+        }
+    }
+
+    if options.days.is_empty() {
+        options.days = aoc2024::SOLUTIONS.iter().map(|solution| solution.day()).collect();
+    }
+    let mut seen = HashSet::new();
+    options.days.retain(|day| seen.insert(*day));
+
+    options.data_dir = input::resolve_data_dir(data_dir.as_deref(), env::var_os(input::DATA_DIR_VAR));
+    options.timings = timings.unwrap_or(match command {
+        Command::Run if !options.report_to_stdout() => Timings::Stdout,
+        _ => Timings::Off,
+    });
+
+    if verbosity > 0 {
+        options.log.default = Level::from_verbosity(verbosity);
+    }
+    for spec in &log_specs {
+        options.log.add_directives(spec)?;
+    }
+
+    if options.report_output.is_some() && options.report.is_none() {
+        return Err("--output requires --format".to_string());
+    }
+    if options.report.is_some() && !matches!(command, Command::Run | Command::Verify) {
+        return Err("--format can only be used with run and verify".to_string());
+    }
+    match &mut options.frames {
+        Some(frames) => {
+            frames.format = frame_format.unwrap_or(frames.format);
+            frames.scale = frame_scale.unwrap_or(frames.scale);
+        }
+        None if frame_format.is_some() || frame_scale.is_some() => {
+            return Err("--frame-format and --scale require --frames".to_string());
+        }
+        None => {}
+    }
+    if options.frames.is_some() && command != Command::Run {
+        return Err("--frames can only be used with run".to_string());
+    }
+    match &mut options.visualize {
+        Some(visualize) => visualize.fps = fps.unwrap_or(visualize.fps),
+        None if fps.is_some() => return Err("--fps requires --visualize".to_string()),
+        None => {}
+    }
+    if options.visualize.is_some() && command != Command::Run {
+        return Err("--visualize can only be used with run".to_string());
+    }
+    if options.visualize.is_some() && options.jobs > 1 {
+        return Err("--visualize draws one day at a time and cannot be used with --jobs".to_string());
+    }
+    if options.profile_alloc && command != Command::Run {
+        return Err("--profile-alloc can only be used with run".to_string());
+    }
+    if (options.baseline.is_some() || options.save_baseline.is_some()) && command != Command::Bench {
+        return Err("--baseline and --save-baseline can only be used with bench".to_string());
+    }
+    match threshold {
+        Some(_) if options.baseline.is_none() => return Err("--threshold requires --baseline".to_string()),
+        Some(threshold) => options.threshold = threshold,
+        None => {}
+    }
+    if options.input.is_some() && options.days.len() != 1 {
+        return Err("--input can only be used with a single day".to_string());
+    }
+
+    Ok(options)
+}
+
+fn value_of(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("option `{}` requires a value", flag))
+}
+
+fn parse_count(value: &str, min: usize) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if count >= min => Ok(count),
+        _ => Err(format!("invalid count `{}`, expected a number of at least {}", value, min)),
+    }
+}
+
+fn baseline_name(name: String) -> Result<String, String> {
+    baseline::validate_name(&name)?;
+    Ok(name)
+}
+
+/// Parses a percentage such as `5%` or `2.5` into a fraction.
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    match value.trim_end_matches('%').parse::<f64>() {
+        Ok(percent) if percent.is_finite() && percent >= 0.0 => Ok(percent / 100.0),
+        _ => Err(format!("invalid threshold `{}`, expected a percentage such as `5%`", value)),
+    }
+}
+
+/// Parses a day selection such as `1-5,12` into the listed day numbers, in order.
+pub fn parse_days(spec: &str) -> Result<Vec<u8>, String> {
+    let mut days = Vec::new();
+
+    for item in spec.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        match item.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_day(from)?, parse_day(to)?);
+                if from > to {
+                    return Err(format!("invalid day range `{}`, {} is after {}", item, from, to));
+                }
+                days.extend(from..=to);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+
+    if days.is_empty() {
+        return Err(format!("no days in `{}`", spec));
+    }
+
+    Ok(days)
+}
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    match day.trim().parse::<u8>() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(format!("invalid day `{}`, expected a number from 1 to 25", day.trim())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+    use std::assert_eq;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("1-5,12"), Ok(vec![1, 2, 3, 4, 5, 12]));
+        assert_eq!(parse_days("7"), Ok(vec![7]));
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("5-3").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_defaults_to_run() {
+        let options = parse_args(args("3 10-11")).unwrap();
+
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.days, vec![3, 10, 11]);
+        assert_eq!(options.parts, Part::BOTH.to_vec());
+        assert!(matches!(options.input_source(3), InputSource::File(path) if path.ends_with("day03.txt")));
+    }
+
+    #[test]
+    fn test_parse_args_with_options() {
+        let options = parse_args(args("verify 17 --part 2 --data-dir inputs")).unwrap();
+
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.days, vec![17]);
+        assert_eq!(options.parts, vec![Part::Two]);
+        assert_eq!(options.input_source(17), InputSource::File(PathBuf::from("inputs/day17.txt")));
+    }
+
+    #[test]
+    fn test_parse_args_inputs() {
+        let options = parse_args(args("7 -d inputs --input-name example")).unwrap();
+        assert_eq!(options.input_source(7), InputSource::File(PathBuf::from("inputs/day07.example.txt")));
+
+        let options = parse_args(args("7 --input -")).unwrap();
+        assert_eq!(options.input_source(7), InputSource::Stdin);
+    }
+
+    #[test]
+    fn test_parse_args_bench_budget() {
+        let options = parse_args(args("bench 1-3 -n 50 --warmup 0")).unwrap();
+        assert_eq!(options.bench, BenchConfig { warmup: 0, budget: Budget::Iterations(50) });
+
+        let options = parse_args(args("bench --budget 250ms")).unwrap();
+        assert_eq!(options.bench.budget, Budget::Time(Duration::from_millis(250)));
+
+        assert!(parse_args(args("bench -n 0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_timings() {
+        assert_eq!(parse_args(args("run 1")).unwrap().timings, Timings::Stdout);
+        assert_eq!(parse_args(args("verify 1")).unwrap().timings, Timings::Off);
+        assert_eq!(parse_args(args("1 --timings stderr")).unwrap().timings, Timings::Stderr);
+        assert!(parse_args(args("1 --timings loud")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_report() {
+        let options = parse_args(args("run 1-3 --format json")).unwrap();
+        assert_eq!(options.report, Some(Format::Json));
+        assert!(options.report_to_stdout());
+        assert_eq!(options.timings, Timings::Off);
+
+        let options = parse_args(args("verify -f csv -o report.csv")).unwrap();
+        assert_eq!(options.report_output, Some(PathBuf::from("report.csv")));
+        assert!(!options.report_to_stdout());
+
+        assert!(parse_args(args("run -f xml")).is_err());
+        assert!(parse_args(args("run -o report.csv")).is_err());
+        assert!(parse_args(args("bench -f json")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_jobs() {
+        assert_eq!(parse_args(args("run")).unwrap().jobs, 1);
+        assert_eq!(parse_args(args("run -j 4")).unwrap().jobs, 4);
+        assert!(parse_args(args("run --jobs auto")).unwrap().jobs >= 1);
+        assert!(parse_args(args("run --jobs 0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_timeout() {
+        assert_eq!(parse_args(args("run")).unwrap().timeout, None);
+        assert_eq!(parse_args(args("verify -T 30s")).unwrap().timeout, Some(Duration::from_secs(30)));
+        assert!(parse_args(args("run --timeout soon")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_frames() {
+        assert_eq!(parse_args(args("run 14")).unwrap().frames, None);
+
+        let frames = parse_args(args("run 14 --frames out --scale 2 --frame-format gif")).unwrap().frames.unwrap();
+        assert_eq!(frames, FrameConfig { directory: PathBuf::from("out"), format: render::Format::Gif, scale: 2 });
+        assert_eq!(parse_args(args("run 6 --frames out")).unwrap().frames.unwrap().format, render::Format::Png);
+
+        assert!(parse_args(args("run 14 --scale 2")).is_err());
+        assert!(parse_args(args("run 14 --frames out --frame-format bmp")).is_err());
+        assert!(parse_args(args("verify 14 --frames out")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_visualize() {
+        assert_eq!(parse_args(args("run 15")).unwrap().visualize, None);
+        assert_eq!(parse_args(args("run 15 --visualize")).unwrap().visualize, Some(VisualizeConfig { fps: 30 }));
+        assert_eq!(parse_args(args("15 --fps 0 --visualize")).unwrap().visualize, Some(VisualizeConfig { fps: 0 }));
+
+        assert!(parse_args(args("run 15 --fps 10")).is_err());
+        assert!(parse_args(args("bench 15 --visualize")).is_err());
+        assert!(parse_args(args("run --visualize -j 4")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_profile_alloc() {
+        assert!(!parse_args(args("run 4")).unwrap().profile_alloc);
+        assert!(parse_args(args("4 24 --profile-alloc -j 2")).unwrap().profile_alloc);
+        assert!(parse_args(args("bench 4 --profile-alloc")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_baselines() {
+        let options = parse_args(args("bench 1-5 --baseline main --save-baseline main --threshold 10%")).unwrap();
+        assert_eq!(options.baseline.as_deref(), Some("main"));
+        assert_eq!(options.save_baseline.as_deref(), Some("main"));
+        assert!((options.threshold - 0.1).abs() < 1e-9);
+        assert_eq!(parse_args(args("bench --baseline main")).unwrap().threshold, 0.05);
+
+        assert!(parse_args(args("run --baseline main")).is_err());
+        assert!(parse_args(args("bench --threshold 10%")).is_err());
+        assert!(parse_args(args("bench --baseline main --threshold lots")).is_err());
+        assert!(parse_args(args("bench --save-baseline ../main")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_log() {
+        assert_eq!(parse_args(args("run 17")).unwrap().log, LogConfig::default());
+        assert_eq!(parse_args(args("run 17 -v")).unwrap().log.default, Level::Debug);
+        assert_eq!(parse_args(args("run 17 --verbose")).unwrap().log.default, Level::Debug);
+        assert_eq!(parse_args(args("run 17 -vv")).unwrap().log.default, Level::Trace);
+        assert_eq!(parse_args(args("run 17 -v --verbose -vv")).unwrap().log.default, Level::Trace);
+
+        let log = parse_args(args("17 23 --log day17=trace -v --log day23=debug,error")).unwrap().log;
+        assert_eq!(log.default, Level::Error);
+        assert_eq!(log.level("day17"), Level::Trace);
+        assert_eq!(log.level("day23"), Level::Debug);
+
+        assert!(parse_args(args("run 17 --log day17=chatty")).is_err());
+        assert!(parse_args(args("run 17 -vx")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(args("run --part 3")).is_err());
+        assert!(parse_args(args("run 1 --frobnicate")).is_err());
+        assert!(parse_args(args("run 1-2 --input day01.txt")).is_err());
+        assert!(parse_args(args("run --data-dir")).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_selects_every_day() {
+        assert_eq!(parse_args(args("list")).unwrap().days, (1..=25).collect::<Vec<u8>>());
+        assert_eq!(parse_args(Vec::new()).unwrap().command, Command::Help);
+    }
+}
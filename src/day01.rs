@@ -1,13 +1,32 @@
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const NAME: &'static str = "Historian Hysteria";
+
+    type Input<'a> = (Vec<i32>, Vec<i32>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        prepare_numbers_columns(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-fn part_one(input: &str) {
-    let (mut column1, mut column2) = prepare_numbers_columns(input);
+fn part_one(columns: &(Vec<i32>, Vec<i32>)) -> i32 {
+    let (mut column1, mut column2) = columns.clone();
 
     column1.sort();
     column2.sort();
@@ -18,30 +37,28 @@ fn part_one(input: &str) {
         .map(|(a, b)| (a - b).abs())
         .sum();
 
-    println!("Day 1, part 1 result: {}", sum_of_differences);
+    sum_of_differences
 }
 
 #[measure_time]
-fn part_two(input: &str) {
-    let (column1, column2) = prepare_numbers_columns(input);
+fn part_two(columns: &(Vec<i32>, Vec<i32>)) -> i32 {
+    let (column1, column2) = columns;
     let sum_of_weights: i32 = column1.iter().map(|&number| number * (column2.iter().filter(|&&x| x == number).count() as i32)).sum();
 
-    println!("Day 1, part 2 result: {}", sum_of_weights);
+    sum_of_weights
 }
 
-fn prepare_numbers_columns(input: &str) -> (Vec<i32>, Vec<i32>) {
+fn prepare_numbers_columns(input: &str) -> Result<(Vec<i32>, Vec<i32>), ParseError> {
     let mut column1: Vec<i32> = Vec::new();
     let mut column2: Vec<i32> = Vec::new();
 
     for line in input.lines() {
-        let numbers: Vec<&str> = line.split_whitespace().collect();
-
-        if let Ok(num1) = numbers[0].parse::<i32>() {
-            column1.push(num1);
-        }
-        if let Ok(num2) = numbers[1].parse::<i32>() {
-            column2.push(num2);
-        }
+        let mut numbers = line.split_whitespace();
+        let num1 = numbers.next().ok_or_else(|| ParseError::after(input, line, "a number"))?;
+        let num2 = numbers.next().ok_or_else(|| ParseError::after(input, num1, "a second number"))?;
+
+        column1.push(parse::number(input, num1)?);
+        column2.push(parse::number(input, num2)?);
     }
-    (column1, column2)
+    Ok((column1, column2))
 }
@@ -1,16 +1,33 @@
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run(input: &str) {
-    let reports: Vec<Vec<i32>> = input.lines().map(|line| {
-        line.split_whitespace().filter_map(|num: &str| num.parse::<i32>().ok()).collect()
-    }).collect();
+pub struct Day02;
 
-    part_one(&reports);
-    part_two(&reports);
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const NAME: &'static str = "Red-Nosed Reports";
+
+    type Input<'a> = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        input.lines().map(|line| {
+            line.split_whitespace().map(|num: &str| parse::number::<i32>(input, num)).collect()
+        }).collect()
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-fn part_one(reports: &Vec<Vec<i32>>) {
+fn part_one(reports: &[Vec<i32>]) -> usize {
     let mut safe_reports = reports.len();
 
     for report in reports {
@@ -19,11 +36,11 @@ fn part_one(reports: &Vec<Vec<i32>>) {
         }
     }
 
-    println!("Day 2, part 1 result: {}", safe_reports);
+    safe_reports
 }
 
 #[measure_time]
-fn part_two(reports: &Vec<Vec<i32>>) {
+fn part_two(reports: &[Vec<i32>]) -> usize {
     let mut safe_reports = reports.len();
 
     for report in reports.iter() {
@@ -38,13 +55,13 @@ fn part_two(reports: &Vec<Vec<i32>>) {
                 }
             }
 
-            if !(sub_reports.len() > sub_reports_failures) {
+            if sub_reports.len() <= sub_reports_failures {
                 safe_reports -= 1;
             }
         }
     }
 
-    println!("Day 2, part 2 result: {}", safe_reports);
+    safe_reports
 }
 
 fn generate_sub_reports(report: &[i32]) -> Vec<Vec<i32>> {
@@ -57,7 +74,7 @@ fn generate_sub_reports(report: &[i32]) -> Vec<Vec<i32>> {
     sub_reports
 }
 
-fn check_report(report: &Vec<i32>) -> (bool, usize) {
+fn check_report(report: &[i32]) -> (bool, usize) {
     let mut change_direction = 0;
     for (i, pair) in report.windows(2).enumerate() {
         let level_change = pair[0] - pair[1];
@@ -69,5 +86,5 @@ fn check_report(report: &Vec<i32>) -> (bool, usize) {
         }
         change_direction = level_change;
     }
-    return (true, 0);
+    (true, 0)
 }
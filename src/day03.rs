@@ -1,13 +1,33 @@
 use regex::{Regex};
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-pub fn run(input: &str) {
-    part_one(&input);
-    part_two(&input);
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const NAME: &'static str = "Mull It Over";
+
+    type Input<'a> = &'a str;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-fn part_one(input: &&str) {
+fn part_one(input: &str) -> i32 {
     let re = Regex::new(r"mul\((\d+),\s*(\d+)\)").unwrap();
 
     let result: i32 = input.lines().flat_map(|line| {
@@ -19,19 +39,24 @@ fn part_one(input: &&str) {
         })
     }).sum();
 
-    println!("Day 3, part 1 result: {}", result);
+    result
 }
 
 #[measure_time]
-fn part_two(input: &&str) {
+fn part_two(input: &str) -> i32 {
     const DO_INSTRUCTION: &str = "do()";
     const DONT_INSTRUCTION: &str = "don't()";
 
+    let mut enabled = true;
     let lines_instructions: Vec<bool> = input.lines().map(|line| {
-        let do_pos = line.rfind(DO_INSTRUCTION).unwrap();
-        let dont_pos = line.rfind(DONT_INSTRUCTION).unwrap();
+        match (line.rfind(DO_INSTRUCTION), line.rfind(DONT_INSTRUCTION)) {
+            (Some(do_pos), Some(dont_pos)) => enabled = do_pos > dont_pos,
+            (Some(_), None) => enabled = true,
+            (None, Some(_)) => enabled = false,
+            (None, None) => {}
+        }
 
-        do_pos > dont_pos
+        enabled
     }).collect();
 
 
@@ -42,7 +67,7 @@ fn part_two(input: &&str) {
             move |caps| {
                 let num1: i32 = caps[1].parse().unwrap();
                 let num2: i32 = caps[2].parse().unwrap();
-                println!("Checking mul({},{})", num1, num2);
+                log::trace!("Checking mul({},{})", num1, num2);
                 let match_index: i32 = caps.get(0).unwrap().start() as i32;
 
                 if let Some(substring) = instruction.get(..match_index as usize) {
@@ -63,6 +88,6 @@ fn part_two(input: &&str) {
         })
     }).sum();
 
-    println!("Day 3, part 2 result: {}", res);
+    res
 }
 
@@ -1,84 +1,68 @@
 use measure_time_macro::measure_time;
-use crate::utils::parse_to_grid;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::{Grid, Position};
 
-pub fn run(input: &str) {
-    let grid = parse_to_grid(input);
+pub struct Day04;
 
-    part_one(grid.clone());
-    part_two(grid.clone());
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const NAME: &'static str = "Ceres Search";
+
+    type Input<'a> = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Grid::parse(input, "a letter", Some)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-fn part_one(grid: Vec<Vec<char>>) {
+fn part_one(grid: &Grid<char>) -> i32 {
     const XMAS: &str = "XMAS";
+    let result: i32 = grid.positions().map(|position| match_word_line_in_grid(grid, position, XMAS)).sum::<i32>();
 
-    let result: i32 = grid.iter().enumerate().map(|(x, row)| row.iter().enumerate().map(|(y, _value)| match_word_line_in_grid(grid.clone(), x, y, XMAS)).sum::<i32>()).sum::<i32>();
-
-    println!("Day 4, part 1 result: {}", result);
+    result
 }
 
-fn match_word_line_in_grid(grid: Vec<Vec<char>>, x: usize, y: usize, word: &str) -> i32 {
-    let height = grid.len();
-    let width = grid[0].len();
-    let mut count: i32 = 0;
+fn match_word_line_in_grid(grid: &Grid<char>, start: Position, word: &str) -> i32 {
+    const DIRECTIONS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, 1), (0, -1), (1, -1), (1, 0), (1, 1)];
 
-    if grid[x][y] != word.chars().next().unwrap() {
+    if grid[start] != word.chars().next().unwrap() {
         return 0
     }
 
-    let word_length = word.len();
-
-    let x_dirs: [i32; 8] = [-1, -1, -1, 0, 0, 1, 1, 1];
-    let y_dirs: [i32; 8] = [-1, 0, 1, 1, -1, -1, 0, 1];
-
-    for dir_i in 0..x_dirs.len() {
-
-        let mut curr_x: i32 = x as i32;
-        let mut curr_y: i32 = y as i32;
-
-        for k in 1..word_length {
-            curr_x += x_dirs[dir_i];
-            curr_y += y_dirs[dir_i];
-
-            if curr_x < 0 || curr_x >= height as i32 || curr_y < 0 || curr_y >= width as i32 { break }
-            if word.chars().nth(k).unwrap() != grid[curr_x as usize][curr_y as usize] { break }
-
-            if k == word_length - 1 { count += 1 }
-        }
-    }
-    count
+    DIRECTIONS.iter()
+        .filter(|&&step| grid.ray(start, step).map(|position| grid[position]).take(word.len()).eq(word.chars()))
+        .count() as i32
 }
 
-fn match_x_mas_in_grid(grid: Vec<Vec<char>>, x: usize, y: usize) -> i32 {
+fn match_x_mas_in_grid(grid: &Grid<char>, center: Position) -> i32 {
     const CENTER_CHAR: char = 'A';
+    const CORNERS: [(isize, isize); 4] = [(-1, -1), (1, 1), (-1, 1), (1, -1)];
 
-    let height = grid.len();
-    let width = grid[0].len();
-
-    if grid[x][y] != CENTER_CHAR {
+    if grid[center] != CENTER_CHAR {
         return 0
     }
 
-    let x_dirs: [i32; 4] = [-1, 1, -1, 1];
-    let y_dirs: [i32; 4] = [-1, 1, 1, -1];
-
-    let result_array: Vec<char> = (0..x_dirs.len()).map(|dir_i| {
-        let curr_x: i32 = x as i32 + x_dirs[dir_i];
-        let curr_y: i32 = y as i32 + y_dirs[dir_i];
-
-        if curr_x < 0 || curr_x >= height as i32 || curr_y < 0 || curr_y >= width as i32 { return 'X' }
-
-        grid[curr_x as usize][curr_y as usize]
+    let result_array: Vec<char> = CORNERS.iter().map(|&step| {
+        grid.offset(center, step).map_or('X', |position| grid[position])
     }).collect();
 
     if result_array[0] == result_array[1] || result_array[2] == result_array[3] || result_array.iter().filter(|&&cr| cr == 'M').count() != 2 || result_array.iter().filter(|&&cr| cr == 'S').count() != 2 { 0 } else { 1 }
 }
 
 #[measure_time]
-fn part_two(grid: Vec<Vec<char>>) {
+fn part_two(grid: &Grid<char>) -> i32 {
+    let result: i32 = grid.positions().map(|position| match_x_mas_in_grid(grid, position)).sum::<i32>();
 
-    let result: i32 = grid.iter().enumerate().map(|(x, row)| row.iter().enumerate().map(|(y, _value)| match_x_mas_in_grid(grid.clone(), x, y)).sum::<i32>()).sum::<i32>();
-
-    println!("Day 4, part 2 result: {}", result);
+    result
 }
-
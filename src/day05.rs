@@ -1,37 +1,59 @@
 use std::collections::HashMap;
 use measure_time_macro::measure_time;
-use crate::utils::{find_element, get_middle_number, split_around_pivot};
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+use crate::utils::{find_element, get_middle_number, parsing, split_around_pivot};
 
-pub fn run(input: &str) {
-    let parts: Vec<&str> = input.split("\n\n").collect();
-    let instruction_orders: Vec<Vec<i32>> = parts[0].lines().map(|line| line.split("|").map(|num| num.parse::<i32>().unwrap()).collect()).collect();
-    let instructions: Vec<Vec<i32>> = parts[1].lines().map(|line| line.split(",").map(|num| num.parse::<i32>().unwrap()).collect()).collect();
+pub struct Day05;
 
-    part_one(instruction_orders.clone(), instructions.clone());
-    part_two(instruction_orders.clone(), instructions.clone());
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const NAME: &'static str = "Print Queue";
+
+    type Input<'a> = (Vec<Vec<i32>>, Vec<Vec<i32>>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (raw_orders, raw_instructions) = parsing::two_blocks(input, "the updates")?;
+        let instruction_orders: Vec<Vec<i32>> = raw_orders.lines().map(|line| {
+            let (before, after) = parsing::separated(input, line, "|")?;
+            Ok(vec![parse::number(input, before)?, parse::number(input, after)?])
+        }).collect::<Result<_, ParseError>>()?;
+        let instructions: Vec<Vec<i32>> = raw_instructions.lines().map(|line| line.split(',').map(|num| parse::number(input, num)).collect()).collect::<Result<_, _>>()?;
+
+        Ok((instruction_orders, instructions))
+    }
+
+    fn part_one((instruction_orders, instructions): &Self::Input<'_>) -> Answer {
+        part_one(instruction_orders.clone(), instructions.clone()).into()
+    }
+
+    fn part_two((instruction_orders, instructions): &Self::Input<'_>) -> Answer {
+        part_two(instruction_orders.clone(), instructions.clone()).into()
+    }
 }
 
 #[measure_time]
-fn part_one(instruction_order: Vec<Vec<i32>>, instructions: Vec<Vec<i32>>) {
+fn part_one(instruction_order: Vec<Vec<i32>>, instructions: Vec<Vec<i32>>) -> i32 {
     let mut elements_map: HashMap<i32, Vec<i32>> = HashMap::new();
     let mut result = 0;
 
-    for k in 0..instructions.len() {
+    for instruction_list in &instructions {
         let mut instruction_result: bool = true;
 
-        for (index, &instruction) in instructions[k].iter().enumerate() {
-            if !elements_map.contains_key(&instruction) {
+        for (index, &instruction) in instruction_list.iter().enumerate() {
+            elements_map.entry(instruction).or_insert_with(|| {
                 let mut elements_after = Vec::new();
 
                 instruction_order.iter()
                     .filter(|order| order[0] == instruction)
                     .for_each(|order| elements_after.push(order[1]));
-                elements_map.insert(instruction, elements_after);
-            }
+                elements_after
+            });
 
             let elements_after_order = elements_map.get(&instruction).unwrap();
 
-            let (elements_before, _elements_after) = split_around_pivot(instructions[k].clone(), index);
+            let (elements_before, _elements_after) = split_around_pivot(instruction_list.clone(), index);
 
             if elements_before.iter().any(|elem| find_element(elements_after_order, elem).is_some()) {
                 instruction_result = false;
@@ -39,14 +61,14 @@ fn part_one(instruction_order: Vec<Vec<i32>>, instructions: Vec<Vec<i32>>) {
             }
         }
 
-        if instruction_result { result += get_middle_number(&instructions[k]).unwrap() }
+        if instruction_result { result += get_middle_number(instruction_list).unwrap() }
     }
 
-    println!("Day 5, part 1 result: {}", result);
+    result
 }
 
 #[measure_time]
-fn part_two(instruction_order: Vec<Vec<i32>>, instructions: Vec<Vec<i32>>) {
+fn part_two(instruction_order: Vec<Vec<i32>>, instructions: Vec<Vec<i32>>) -> i32 {
     let mut elements_map: HashMap<i32, Vec<i32>> = HashMap::new();
 
     for order_pair in instruction_order {
@@ -62,20 +84,22 @@ fn part_two(instruction_order: Vec<Vec<i32>>, instructions: Vec<Vec<i32>>) {
 
     let mut result = 0;
 
-    for k in 0..instructions.len() {
-        for (index, &instruction) in instructions[k].iter().enumerate() {
-            let elements_after_order = elements_map.get(&instruction).unwrap();
+    for instruction_list in &instructions {
+        for (index, &instruction) in instruction_list.iter().enumerate() {
+            let Some(elements_after_order) = elements_map.get(&instruction) else {
+                continue;
+            };
 
-            let (elements_before, _elements_after) = split_around_pivot(instructions[k].clone(), index);
+            let (elements_before, _elements_after) = split_around_pivot(instruction_list.clone(), index);
 
             if elements_before.iter().any(|elem| find_element(elements_after_order, elem).is_some()) {
-                let correct_list = sort_pages(&instructions[k], &elements_map);
+                let correct_list = sort_pages(instruction_list, &elements_map);
                 result += get_middle_number(&correct_list).unwrap();
                 break;
             }
         }
     }
-    println!("Day 5, part 2 result: {}", result);
+    result
 }
 
 fn sort_pages(page_list: &[i32], page_order: &HashMap<i32, Vec<i32>>) -> Vec<i32> {
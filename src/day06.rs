@@ -1,185 +1,113 @@
-use std::collections::HashSet;
 use measure_time_macro::measure_time;
-use crate::utils::{count_char_in_grid, get_element_from_grid, parse_to_grid};
-
-const DIRECTIONS: [char; 4] = ['^', '>', 'v', '<'];
-const DIRECTION_MAP: &[(char, (i32, i32))] = &[
-    ('^', (-1, 0)),
-    ('>', (0, 1)),
-    ('v', (1, 0)),
-    ('<', (0, -1)),
-];
-
-fn get_direction(c: char) -> Option<(i32, i32)> {
-    DIRECTION_MAP.iter().find_map(|&(dir, vector)| {
-        if dir == c {
-            Some(vector)
-        } else {
-            None
-        }
-    })
-}
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::render::terminal::{Scene, Visualizer};
+use crate::render::{self, Image, Palette, Recorder, Rgb};
+use crate::solution::Solution;
+use crate::utils::{BitGrid, Direction, Grid, Position, StateSet};
+
+pub struct Day06;
 
-pub fn run(input: &str) {
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const NAME: &'static str = "Guard Gallivant";
 
-    let grid = parse_to_grid(input);
+    type Input<'a> = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Grid::parse(input, "a map tile", Some)
+    }
 
-    part_one(&mut grid.clone());
-    part_two(&mut grid.clone());
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(&mut input.clone()).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(&mut input.clone()).into()
+    }
 }
 
 #[measure_time]
-fn part_one(mut grid: &mut Vec<Vec<char>>) {
-    let is_guard_on_map = true;
-    let (mut direction, mut guard_current_position, mut guard_current_direction) = find_guard_position_with_direction(grid).unwrap();
-    let mut current_direction_index = DIRECTIONS.iter().position(|&d| d == direction).unwrap();
-
-    while is_guard_on_map {
-        if move_guard(
-            &mut grid,
-            &mut direction,
-            &mut guard_current_position,
-            &mut guard_current_direction,
-            &mut current_direction_index,
-        ) {
-            break;
-        }
+fn part_one(grid: &mut Grid<char>) -> usize {
+    let mut guard = find_guard(grid).unwrap();
+    let mut recorder = Recorder::new(Day06::DAY, "guard");
+    let symbols = Palette::new([90, 90, 100]).with('#', [170, 170, 170]).with('X', [70, 130, 180]);
+    let mut visualizer = Visualizer::new(Day06::DAY, "guard", symbols);
+
+    while !move_guard(grid, &mut guard) {
+        recorder.frame(|| picture(grid, guard));
+        visualizer.frame(|| scene(grid, guard));
     }
 
-    println!("Day 6, part 1 result: {}", count_char_in_grid(grid, 'X'));
+    grid.find_all(|&cell| cell == 'X').count()
 }
 
-fn move_guard(grid: &mut Vec<Vec<char>>, direction: &mut char, guard_current_position: &mut (usize, usize), guard_current_direction: &mut (i32, i32), current_direction_index: &mut usize) -> bool {
-    let new_position = move_position_in_direction(guard_current_position, guard_current_direction);
-    if new_position.is_none() || new_position.unwrap().1 >= grid.len() || new_position.unwrap().0 >= grid[0].len() {
-        grid[guard_current_position.0][guard_current_position.1] = 'X';
+fn move_guard(grid: &mut Grid<char>, (position, direction): &mut Guard) -> bool {
+    let Some(new_position) = grid.step(*position, *direction) else {
+        grid[*position] = 'X';
         return true;
-    }
+    };
 
-    let map_element: char = get_element_from_grid(grid, new_position.unwrap());
-    if map_element != '#' {
-        grid[guard_current_position.0][guard_current_position.1] = 'X';
-        *guard_current_position = new_position.unwrap();
+    if grid[new_position] != '#' {
+        grid[*position] = 'X';
+        *position = new_position;
     } else {
-        *current_direction_index = (*current_direction_index + 1usize) % DIRECTIONS.len();
-        *direction = DIRECTIONS[*current_direction_index];
-        *guard_current_direction = get_direction(DIRECTIONS[*current_direction_index]).unwrap();
+        *direction = direction.turn_right();
     }
     false
 }
 
 #[measure_time]
-fn part_two(mut grid: &mut Vec<Vec<char>>) {
-    let clean_grid = grid.clone();
-
-    let (mut direction, mut guard_current_position, mut guard_current_direction) = find_guard_position_with_direction(&clean_grid).unwrap();
-    let mut current_direction_index = DIRECTIONS.iter().position(|&d| d == direction).unwrap();
-    let mut possible_blockers_positions: HashSet<(usize, usize)> = HashSet::new();
-    let starting_position = guard_current_position;
-
-    loop {
-        if move_guard(
-            &mut grid,
-            &mut direction,
-            &mut guard_current_position,
-            &mut guard_current_direction,
-            &mut current_direction_index,
-        ) {
-            break;
-        }
-        possible_blockers_positions.insert(guard_current_position);
+fn part_two(grid: &mut Grid<char>) -> usize {
+    let mut blocked_grid = grid.clone();
+    let start = find_guard(grid).unwrap();
+    let mut guard = start;
+    let mut possible_blockers_positions = BitGrid::for_grid(grid);
+
+    while !move_guard(grid, &mut guard) {
+        possible_blockers_positions.insert(guard.0);
     }
-    possible_blockers_positions.remove(&starting_position);
+    possible_blockers_positions.remove(start.0);
+
+    let mut seen = StateSet::for_grid(grid, Direction::ALL.len());
+    possible_blockers_positions.iter().filter(|&blocker_position| {
+        blocked_grid[blocker_position] = '#';
+        let is_loop = has_loop(&mut blocked_grid, start, &mut seen);
+        blocked_grid[blocker_position] = '.';
+        is_loop
+    }).count()
+}
 
-    let response: usize = possible_blockers_positions.iter().map(|blocker_position|
-        {
-            let mut blocked_grid = clean_grid.clone();
-            blocked_grid[blocker_position.0][blocker_position.1] = '#';
-            blocked_grid
-        }
-    ).filter(| blocked_grid| has_loop(blocked_grid)).count();
+type Guard = (Position, Direction);
 
+const GUARD_COLOUR: Rgb = [255, 200, 0];
 
-    println!("Day 6, part 2 result: {}", response);
+fn picture(grid: &Grid<char>, (position, _): Guard) -> Image {
+    let palette = Palette::new([20, 20, 30]).with('#', [110, 110, 110]).with('X', [70, 130, 180]);
+    let mut image = render::render(grid, &palette);
+    image[position] = GUARD_COLOUR;
+    image
 }
 
-fn find_guard_position_with_direction(
-    grid: &Vec<Vec<char>>,
-) -> Option<(char, (usize, usize), (i32, i32))> {
-    for (row_index, row) in grid.iter().enumerate() {
-        for (col_index, &cell) in row.iter().enumerate() {
-            if let Some(direction) = get_direction(cell) {
-                return Some((cell, (row_index, col_index), direction));
-            }
-        }
-    }
-    None
+fn scene(grid: &Grid<char>, (position, direction): Guard) -> Scene {
+    let mut grid = grid.clone();
+    grid[position] = direction.arrow();
+    Scene::new(grid).with_actor(position)
 }
 
-fn move_position_in_direction(
-    position: &mut (usize, usize),
-    direction: &mut (i32, i32),
-) -> Option<(usize, usize)> {
-    let val0 = position.0 as i32 + direction.0;
-    let val1 = position.1 as i32 + direction.1;
-
-    if val0 > 0 && val1 > 0 { Some((val0 as usize, val1 as usize)) } else { None }
+fn find_guard(grid: &Grid<char>) -> Option<Guard> {
+    let position = grid.find(|&cell| Direction::from_arrow(cell).is_some())?;
+    Some((position, Direction::from_arrow(grid[position])?))
 }
 
-fn has_loop(grid_template: &Vec<Vec<char>>) -> bool {
-    let mut grid = grid_template.clone();
-    let mut fast_grid = grid_template.clone();
-    let mut is_loop = false;
-
-    let (mut direction, mut guard_current_position, mut guard_current_direction) = find_guard_position_with_direction(&grid_template).unwrap();
-    let mut current_direction_index = DIRECTIONS.iter().position(|&d| d == direction).unwrap();
-
-    let (mut fast_direction, mut fast_guard_current_position, mut fast_guard_current_direction) = find_guard_position_with_direction(&grid_template).unwrap();
-    let mut fast_current_direction_index = DIRECTIONS.iter().position(|&d| d == fast_direction).unwrap();
-
-    loop {
-        let guard_map = move_guard(
-            &mut grid,
-            &mut direction,
-            &mut guard_current_position,
-            &mut guard_current_direction,
-            &mut current_direction_index,
-        );
-        let fast_guard_map = if !move_guard(
-            &mut fast_grid,
-            &mut fast_direction,
-            &mut fast_guard_current_position,
-            &mut fast_guard_current_direction,
-            &mut fast_current_direction_index,
-        ) {
-            move_guard(
-                &mut fast_grid,
-                &mut fast_direction,
-                &mut fast_guard_current_position,
-                &mut fast_guard_current_direction,
-                &mut fast_current_direction_index,
-            )
-        } else { true };
-
-        match (guard_map, fast_guard_map) {
-            (true, true) => {
-                break;
-            }
-            (true, false) => {
-                break;
-            }
-            (false, true) => {
-                break;
-            }
-            (false, false) => {
-                if guard_current_position.eq(&fast_guard_current_position) && guard_current_direction.eq(&fast_guard_current_direction)
-                {
-                    is_loop = true;
-                    break;
-                }
-            }
-        };
+/// Walks the guard from `guard` until they leave the map or come back to a position they
+/// already faced the same way from, which means they loop. `seen` is scratch space.
+fn has_loop(grid: &mut Grid<char>, mut guard: Guard, seen: &mut StateSet) -> bool {
+    seen.clear();
+    while seen.insert(guard.0, guard.1.index()) {
+        if move_guard(grid, &mut guard) {
+            return false;
+        }
     }
-
-    is_loop //1910 - 2992
+    true
 }
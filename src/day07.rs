@@ -1,7 +1,10 @@
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
 #[derive(Debug, Clone)]
-struct Equation {
+pub struct Equation {
     result: i64,
     operands: Vec<i64>,
 }
@@ -28,38 +31,47 @@ impl Equation {
     }
 }
 
-pub fn run(input: &str) {
-    let equations = input.lines().map(|line| {
-        let mut parts = line.split(":");
-        let result = parts.next().unwrap().trim().parse::<i64>().expect("Invalid number format");
-        let operands = parts.next().unwrap().split_whitespace().map(|n| n.parse::<i64>().expect("Invalid number format")).collect();
-        Equation::new(result, operands)
-    }).collect();
+pub struct Day07;
 
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const NAME: &'static str = "Bridge Repair";
 
-    part_one(&equations);
-    part_two(&equations);
+    type Input<'a> = Vec<Equation>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        input.lines().map(|line| {
+            let (result, operands) = line.split_once(':').ok_or_else(|| ParseError::after(input, line, "`:`"))?;
+            let result = parse::number::<i64>(input, result.trim())?;
+            let operands = operands.split_whitespace().map(|n| parse::number::<i64>(input, n)).collect::<Result<_, _>>()?;
+            Ok(Equation::new(result, operands))
+        }).collect()
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-fn part_one(equations: &Vec<Equation>) {
+fn part_one(equations: &[Equation]) -> i64 {
     let possible_operations = vec!['+', '*'];
 
-    let result = calculate_calibration_result(equations, possible_operations);
-
-    println!("Day 7, part 1 result: {}", result);
+    calculate_calibration_result(equations, possible_operations)
 }
 
 #[measure_time]
-fn part_two(equations: &Vec<Equation>) {
+fn part_two(equations: &[Equation]) -> i64 {
     let possible_operations = vec!['+', '*', '|'];
 
-    let result = calculate_calibration_result(equations, possible_operations);
-
-    println!("Day 7, part 2 result: {}", result);
+    calculate_calibration_result(equations, possible_operations)
 }
 
-fn calculate_calibration_result(equations: &Vec<Equation>, possible_operations: Vec<char>) -> i64 {
+fn calculate_calibration_result(equations: &[Equation], possible_operations: Vec<char>) -> i64 {
     let result: i64 = equations.iter().filter(|equation| {
         let mut is_valid = false;
         let operation_count = equation.operands.len() - 1;
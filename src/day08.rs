@@ -1,15 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::Grid;
 
 #[derive(Debug, Clone)]
-struct Grid {
-    matrix: Vec<Vec<char>>,
+pub struct City {
+    grid: Grid<char>,
 }
 
-impl Grid {
-    fn new(matrix: Vec<Vec<char>>) -> Self {
+impl City {
+    fn new(grid: Grid<char>) -> Self {
         Self {
-            matrix
+            grid
         }
     }
 
@@ -33,7 +37,7 @@ impl Grid {
                         } else {
                             self.get_antinodes(antenna_positions[i], antenna_positions[j])
                         };
-                    if new_antinodes.len() > 0 {
+                    if !new_antinodes.is_empty() {
                         for antinode in new_antinodes {
                             antinodes.insert(antinode);
                         }
@@ -59,7 +63,7 @@ impl Grid {
             compute_coords(a1.0, a2.0),
             compute_coords(a1.1, a2.1),
         ) {
-            if new_x < self.matrix[0].len() && new_y < self.matrix.len() {
+            if self.grid.contains((new_x, new_y)) {
                 result.push((new_x, new_y));
             }
         }
@@ -68,7 +72,7 @@ impl Grid {
             compute_coords(a2.0, a1.0),
             compute_coords(a2.1, a1.1),
         ) {
-            if new_x < self.matrix[0].len() && new_y < self.matrix.len() {
+            if self.grid.contains((new_x, new_y)) {
                 result.push((new_x, new_y));
             }
         }
@@ -79,14 +83,14 @@ impl Grid {
     fn get_harmonic_antinodes(&self, a1: (usize, usize), a2: (usize, usize)) -> Vec<(usize, usize)> {
         let mut result = Vec::new();
 
-        let step_x = if a2.0 > a1.0 { a2.0 - a1.0 } else { a1.0 - a2.0 };
-        let step_y = if a2.1 > a1.1 { a2.1 - a1.1 } else { a1.1 - a2.1 };
+        let step_x = a2.0.abs_diff(a1.0);
+        let step_y = a2.1.abs_diff(a1.1);
 
         let mut current = a1;
 
         loop {
 
-            if current.0 < self.matrix[0].len() && current.1 < self.matrix.len() {
+            if self.grid.contains(current) {
                 result.push(current);
             } else {
                 break;
@@ -127,14 +131,12 @@ impl Grid {
     fn prepare_antennas_map(&self) -> HashMap<char, Vec<(usize, usize)>> {
         let mut antennas: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
 
-        for i in 0..self.matrix[0].len() {
-            for j in 0..self.matrix.len() {
-                if let Some(antenna) = match self.matrix[j][i] {
-                    '.' => None,
-                    other => Some(other),
-                } {
-                    antennas.entry(antenna).or_insert_with(Vec::new).push((j, i));
-                }
+        for (position, &cell) in self.grid.iter() {
+            if let Some(antenna) = match cell {
+                '.' => None,
+                other => Some(other),
+            } {
+                antennas.entry(antenna).or_default().push(position);
             }
         }
 
@@ -142,24 +144,36 @@ impl Grid {
     }
 }
 
-pub fn run(input: &str) {
-    let grid = Grid::new(input.lines().map(
-        |line| line.chars().collect()
-    ).collect());
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const NAME: &'static str = "Resonant Collinearity";
+
+    type Input<'a> = City;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(City::new(Grid::parse(input, "a map tile", Some)?))
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input.clone()).into()
+    }
 
-    part_one(grid.clone());
-    part_two(grid.clone());
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input.clone()).into()
+    }
 }
 
 #[measure_time]
-fn part_one(grid: Grid) {
-    let result = grid.count_antinodes(false);
+fn part_one(city: City) -> usize {
+    let result = city.count_antinodes(false);
 
-    println!("Day 8, part 1 result: {}", result.len());
+    result.len()
 }
 
 #[measure_time]
-fn part_two(grid: Grid) {
-    let result = grid.count_antinodes(true);
-    println!("Day 8, part 2 result: {}", result.len());
+fn part_two(city: City) -> usize {
+    let result = city.count_antinodes(true);
+    result.len()
 }
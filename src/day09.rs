@@ -1,15 +1,17 @@
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-fn create_memory_array(input: &str) -> Vec<Option<i64>> {
+fn create_memory_array(input: &str) -> Result<Vec<Option<i64>>, ParseError> {
     let mut result = Vec::new();
-    let mut file_id = 0;
     let memory: Vec<usize> = input
-        .chars()
-        .filter_map(|c| c.to_digit(10))
-        .map(|d| d as usize)
-        .collect();
+        .char_indices()
+        .map(|(index, c)| c.to_digit(10).ok_or_else(|| ParseError::at(input, &input[index..index + c.len_utf8()], "a digit")))
+        .map(|d| d.map(|d| d as usize))
+        .collect::<Result<_, _>>()?;
 
-    for pair in memory.chunks(2) {
+    for (file_id, pair) in (0..).zip(memory.chunks(2)) {
         match *pair {
             [file_length, free_space_length] => {
                 result.extend(vec![Some(file_id); file_length]);
@@ -20,22 +22,34 @@ fn create_memory_array(input: &str) -> Vec<Option<i64>> {
             }
             _ => unreachable!(),
         }
-
-        file_id += 1;
     }
 
-    result
+    Ok(result)
 }
 
-pub fn run(input: &str) {
-    let memory_array = create_memory_array(input);
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const NAME: &'static str = "Disk Fragmenter";
+
+    type Input<'a> = Vec<Option<i64>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        create_memory_array(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input.clone()).into()
+    }
 
-    part_one(memory_array.clone());
-    part_two(memory_array.clone());
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input.clone()).into()
+    }
 }
 
 #[measure_time]
-fn part_one(memory: Vec<Option<i64>>) {
+fn part_one(memory: Vec<Option<i64>>) -> i64 {
     let mut result_array = memory;
     loop {
         let first_free_space_index = result_array.iter().position(|memory| memory.is_none()).unwrap();
@@ -49,11 +63,11 @@ fn part_one(memory: Vec<Option<i64>>) {
 
     let result: i64 = result_array.iter().filter(|file_id| file_id.is_some()).enumerate().map(|(index, file_id)| index as i64 * file_id.unwrap()).sum();
 
-    println!("Day 9, part 1 result: {}", result);
+    result
 }
 
 #[measure_time]
-fn part_two(memory: Vec<Option<i64>>) {
+fn part_two(memory: Vec<Option<i64>>) -> i64 {
     let mut result_array = memory;
     let mut current_file_id = result_array.iter().rfind(|memory| memory.is_some()).unwrap().unwrap();
 
@@ -67,34 +81,30 @@ fn part_two(memory: Vec<Option<i64>>) {
 
         let mut current_free_space_index = 0;
 
-        loop {
-            if let Some([free_space_index, free_space_size]) = get_next_free_space(&result_array, current_free_space_index) {
-                if free_space_index > file_index { break };
+        while let Some([free_space_index, free_space_size]) = get_next_free_space(&result_array, current_free_space_index) {
+            if free_space_index > file_index { break };
 
-                if file_size <= free_space_size {
-                    for i in 0..file_size {
-                        result_array[free_space_index + i] = Some(current_file_id);
-                        result_array[file_index + file_size - 1 - i] = None;
-                    }
-                    break;
+            if file_size <= free_space_size {
+                for i in 0..file_size {
+                    result_array[free_space_index + i] = Some(current_file_id);
+                    result_array[file_index + file_size - 1 - i] = None;
                 }
+                break;
+            }
 
-                current_free_space_index = free_space_index + free_space_size;
-            } else { break }
-
-
+            current_free_space_index = free_space_index + free_space_size;
         }
         current_file_id -= 1;
     }
 
     let result: i64 = result_array.iter().enumerate().map(|(index, file_id)| index as i64 * file_id.unwrap_or_else(||0)).sum();
-    println!("Day 9, part 2 result: {}", result);
+    result
 }
 
-fn get_next_free_space(array: &Vec<Option<i64>>, index: usize) -> Option<[usize; 2]> {
-    if let Some(free_space_index) = array.into_iter().skip(index).position(|memory| memory.is_none()) {
+fn get_next_free_space(array: &[Option<i64>], index: usize) -> Option<[usize; 2]> {
+    if let Some(free_space_index) = array.iter().skip(index).position(|memory| memory.is_none()) {
         let current_index = free_space_index + index;
-        let size = current_index + array.into_iter().skip(current_index + 1usize).position(|memory| memory.is_some()).unwrap_or_else(||0) - current_index + 1;
+        let size = current_index + array.iter().skip(current_index + 1usize).position(|memory| memory.is_some()).unwrap_or(0) - current_index + 1;
         return Some([current_index, size])
     }
 
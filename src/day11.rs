@@ -1,77 +1,72 @@
 use std::collections::HashMap;
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run(input: &str) {
-    let stones: Vec<i64> = input.split_whitespace().filter_map(|x| x.parse::<i64>().ok()).collect();
+pub struct Day11;
 
-    part_one(stones.clone());
-    part_two(stones.clone());
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const NAME: &'static str = "Plutonian Pebbles";
+
+    type Input<'a> = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        input.split_whitespace().map(|x| parse::number::<i64>(input, x)).collect()
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input.clone()).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input.clone()).into()
+    }
+}
+
+/// The stones one stone turns into after a single blink.
+fn blink(stone: i64) -> Vec<i64> {
+    match stone {
+        0 => vec![1],
+        engrave if engrave.abs().to_string().len() % 2 == 0 => {
+            let s = engrave.abs().to_string();
+            let (left, right) = s.split_at(s.len() / 2);
+            vec![left.parse::<i64>().unwrap_or(0), right.parse::<i64>().unwrap_or(0)]
+        }
+        _ => vec![stone * 2024]
+    }
 }
 
 #[measure_time]
-fn part_one(stones: Vec<i64>) {
+fn part_one(stones: Vec<i64>) -> usize {
     let mut result = stones;
     for _ in 0..25 {
-        result = result.into_iter().flat_map(|stone| {
-            match stone {
-                engrave if engrave == 0 => vec![1],
-                engrave if engrave.abs().to_string().len() % 2 == 0 => {
-                    let s = engrave.abs().to_string();
-                    let (left, right) = s.split_at(s.len() / 2);
-                    vec![left.parse::<i64>().unwrap_or(0), right.parse::<i64>().unwrap_or(0)]
-                }
-                _ => vec![stone * 2024]
-            }
-        }).collect();
+        result = result.into_iter().flat_map(blink).collect();
     }
 
 
-    println!("Day 11, part 1 result: {:?}", result.len());
+    result.len()
 }
 
+/// Stones engraved with the same number turn into the same stones, so they are counted once per
+/// number.
 #[measure_time]
-fn part_two(stones: Vec<i64>) {
-    let mut result = stones.into_iter().flat_map(|stone| {
-        match stone {
-            engrave if engrave == 0 => vec![1],
-            engrave if engrave.abs().to_string().len() % 2 == 0 => {
-                let s = engrave.abs().to_string();
-                let (left, right) = s.split_at(s.len() / 2);
-                vec![left.parse::<i64>().unwrap_or(0), right.parse::<i64>().unwrap_or(0)]
+fn part_two(stones: Vec<i64>) -> usize {
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for stone in stones {
+        *counts.entry(stone).or_insert(0) += 1;
+    }
+
+    for _ in 0..75 {
+        let mut next_counts = HashMap::with_capacity(counts.len());
+        for (stone, count) in counts {
+            for next in blink(stone) {
+                *next_counts.entry(next).or_insert(0) += count;
             }
-            _ => vec![stone * 2024]
         }
-    }).fold(HashMap::new(), |mut acc, value| {
-        *acc.entry(value).or_insert(0) += 1;
-        acc
-    });
-
-    for _ in 0..74 {
-        let new_result: HashMap<i64, usize> = result
-            .into_iter()
-            .flat_map(|(stone, count)| {
-                let values = match stone {
-                    engrave if engrave == 0 => vec![1],
-                    engrave if engrave.abs().to_string().len() % 2 == 0 => {
-                        let s = engrave.abs().to_string();
-                        let (left, right) = s.split_at(s.len() / 2);
-                        vec![
-                            left.parse::<i64>().unwrap_or(0),
-                            right.parse::<i64>().unwrap_or(0),
-                        ]
-                    }
-                    _ => vec![stone * 2024],
-                };
-
-                values.into_iter().map(move |val| (val, count))
-            })
-            .fold(HashMap::new(), |mut acc, (value, count)| {
-                *acc.entry(value).or_insert(0) += count;
-                acc
-            });
-
-        result = new_result;
+        counts = next_counts;
     }
 
-    println!("Day 11, part 2 result: {}", result.values().sum::<usize>());
+    counts.values().sum()
 }
@@ -1,7 +1,10 @@
 use measure_time_macro::measure_time;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::parsing;
 
-struct Machine {
+pub struct Machine {
     a_button: (f64, f64),
     b_button: (f64, f64),
     prize: (f64, f64),
@@ -9,74 +12,91 @@ struct Machine {
 
 impl Machine {
 
-    pub fn solve(&self) -> Option<f64> {
+    pub fn solve(&self) -> Option<i64> {
         let a = ((self.prize.0 - self.prize.1 * self.b_button.0 / self.b_button.1) / (self.a_button.0 - self.a_button.1 * self.b_button.0 / self.b_button.1)).round();
         let b = ((self.prize.1 - a * self.a_button.1) / self.b_button.1).round();
 
-        return if a * self.a_button.0 + b * self.b_button.0 == self.prize.0 &&
+        if a * self.a_button.0 + b * self.b_button.0 == self.prize.0 &&
             a * self.a_button.1 + b * self.b_button.1 == self.prize.1 &&
             a >= 0f64 && b >= 0f64
-        { Some( a * 3f64 + b) }
+        { Some((a * 3f64 + b) as i64) }
         else
-        { None };
+        { None }
     }
 }
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
-}
+pub struct Day13;
 
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    const NAME: &'static str = "Claw Contraption";
 
-#[measure_time]
-fn part_one(input: &str) -> f64 {
-    let machines = parse(input, 0f64);
+    type Input<'a> = Vec<Machine>;
 
-    let result = machines.iter().filter_map(|machine| machine.solve()).sum();
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
 
-    println!("Day 13, part 1 result: {:?}", result);
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
 
-    result
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
-#[measure_time]
-fn part_two(input: &str) -> f64 {
-    let machines = parse(input, 10000000000000f64);
 
+#[measure_time]
+fn part_one(machines: &[Machine]) -> i64 {
     let result = machines.iter().filter_map(|machine| machine.solve()).sum();
 
-    println!("Day 13, part 2 result: {:?}", result);
-
     result
 }
 
-fn parse(input: &str, offset: f64) -> Vec<Machine> {
-    let re = Regex::new(r"Button A: X\+(?P<ax>\d+), Y\+(?P<ay>\d+)
-Button B: X\+(?P<bx>\d+), Y\+(?P<by>\d+)
-Prize: X=(?P<px>\d+), Y=(?P<py>\d+)").unwrap();
-    let mut machines = vec![];
+#[measure_time]
+fn part_two(machines: &[Machine]) -> i64 {
+    const OFFSET: f64 = 10000000000000f64;
 
-    for captures in re.captures_iter(input) {
-        let ax = captures.name("ax").unwrap().as_str().parse::<f64>().unwrap();
-        let ay = captures.name("ay").unwrap().as_str().parse::<f64>().unwrap();
-        let a = (ax, ay);
+    let result = machines.iter()
+        .map(|machine| Machine { prize: (machine.prize.0 + OFFSET, machine.prize.1 + OFFSET), ..*machine })
+        .filter_map(|machine| machine.solve())
+        .sum();
 
-        let bx = captures.name("bx").unwrap().as_str().parse::<f64>().unwrap();
-        let by = captures.name("by").unwrap().as_str().parse::<f64>().unwrap();
-        let b = (bx, by);
+    result
+}
 
-        let px = captures.name("px").unwrap().as_str().parse::<f64>().unwrap();
-        let py = captures.name("py").unwrap().as_str().parse::<f64>().unwrap();
-        let prize = (px + offset, py + offset);
+fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
+    let patterns = [
+        ("Button A:", "`Button A: X+<n>, Y+<n>`"),
+        ("Button B:", "`Button B: X+<n>, Y+<n>`"),
+        ("Prize:", "`Prize: X=<n>, Y=<n>`"),
+    ];
+    let lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+    let mut machines = vec![];
 
-        machines.push(Machine { a_button: a, b_button: b, prize });
+    for machine_lines in lines.chunks(patterns.len()) {
+        if machine_lines.len() < patterns.len() {
+            return Err(ParseError::end(input, patterns[machine_lines.len()].1));
+        }
+
+        let mut values = vec![];
+        for (&line, (label, expected)) in machine_lines.iter().zip(&patterns) {
+            if !line.starts_with(label) {
+                return Err(ParseError::at(input, line, *expected));
+            }
+            let [x, y] = parsing::signed_ints_n::<f64, 2>(input, line, expected)?;
+            values.push((x, y));
+        }
+
+        machines.push(Machine { a_button: values[0], b_button: values[1], prize: values[2] });
     }
 
-    machines
+    Ok(machines)
 }
 
 #[cfg(test)]
-mod day13 {
+mod tests {
     use crate::day13::*;
     use std::assert_eq;
 
@@ -97,6 +117,6 @@ Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
     #[test]
     fn test1() {
-        assert_eq!(part_one(SAMPLE), 480.00)
+        assert_eq!(part_one(&parse(SAMPLE).unwrap()), 480)
     }
 }
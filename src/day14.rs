@@ -1,59 +1,75 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use measure_time_macro::measure_time;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::ParseError;
+use crate::render::terminal::{Scene, Visualizer};
+use crate::render::{self, Palette, Recorder};
+use crate::solution::Solution;
+use crate::utils::{parsing, BitGrid, Grid, Point, Vec2};
 
 #[derive(Debug, Clone)]
-struct Robot {
-    position: (i32, i32),
-    velocity: (i32, i32)
+pub struct Robot {
+    position: Point,
+    velocity: Vec2
 }
 
 
 impl Robot {
 
-    pub fn predict_future(&self, time: i32, width: i32, height: i32) -> Robot {
-        let mut result_robot = self.clone();
-        for _ in 0..time {
-            result_robot = result_robot.move_me(width, height);
-        }
-
-        return result_robot;
+    pub fn predict_future(&self, time: i64, width: i64, height: i64) -> Robot {
+        let position = self.position.wrapping_add(self.velocity * time, Point::new(width + 1, height + 1));
+        Robot { position, velocity: self.velocity }
     }
 
-    fn move_me(&self, width: i32, height: i32) -> Robot {
-        let mut new_x = self.position.0 + self.velocity.0;
-        let mut new_y = self.position.1 + self.velocity.1;
+}
+
+/// The robots and the room they move in, 101x103 tiles unless the input sets `@width` and
+/// `@height`.
+#[derive(Debug, Clone)]
+pub struct Room {
+    width: i64,
+    height: i64,
+    robots: Vec<Robot>,
+}
+
+pub struct Day14;
 
-        new_x = if new_x < 0 { 1 + width + new_x } else if new_x > width { new_x - width - 1 } else { new_x };
-        new_y = if new_y < 0 { 1 + height + new_y } else if new_y > height { new_y - height - 1 } else { new_y };
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const NAME: &'static str = "Restroom Redoubt";
 
-        Robot { position: (new_x, new_y), velocity: self.velocity }
+    type Input<'a> = Room;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_room(input)
     }
 
-}
+    fn part_one(room: &Self::Input<'_>) -> Answer {
+        part_one(&room.robots, room.width - 1, room.height - 1).into()
+    }
 
-pub fn run(input: &str) {
-    part_one(input, 100, 102);
-    part_two(input, 101, 103);
+    fn part_two(room: &Self::Input<'_>) -> Answer {
+        part_two(&room.robots, room.width, room.height).into()
+    }
 }
 
 
 #[measure_time]
-fn part_one(input: &str, width: i32, height: i32) -> i32 {
-    let quadrants: [(std::ops::Range<i32>, std::ops::Range<i32>); 4] = [
+fn part_one(robots: &[Robot], width: i64, height: i64) -> i32 {
+    let quadrants: [(std::ops::Range<i64>, std::ops::Range<i64>); 4] = [
         (0..width / 2, 0..height / 2),       // Quadrant 1
         (width / 2 + 1..width + 1, 0..height / 2),     // Quadrant 2
         (0..width / 2, height / 2 + 1..height + 1),     // Quadrant 3
         (width / 2 + 1..width + 1, height / 2 + 1..height + 1),   // Quadrant 4
     ];
 
-    let robots = parse(input);
     let mut quadrant_counts: HashMap<i32, i32> = HashMap::new();
 
     robots.iter().map(|robot| robot.predict_future(100, width, height)).for_each(|future_robot| {
         let position = future_robot.position;
         if let Some(quadrant) = quadrants.iter().position(|(x_range, y_range)| {
-            x_range.contains(&position.0) && y_range.contains(&position.1)
+            x_range.contains(&position.x) && y_range.contains(&position.y)
         }) {
             *quadrant_counts.entry((quadrant + 1) as i32).or_insert(0) += 1;
         }
@@ -69,81 +85,73 @@ fn part_one(input: &str, width: i32, height: i32) -> i32 {
         .copied()
         .product();
 
-    println!("Day 14, part 1 result: {:?}", safety_factor);
-
     safety_factor
 }
 
 #[measure_time]
-fn part_two(input: &str, width: i32, height: i32) -> i32 {
-    let robots = parse(input);
-
-    let boundary: (i32, i32) = (width, height);
+fn part_two(robots: &[Robot], width: i64, height: i64) -> i64 {
+    let boundary = Point::new(width, height);
+    let palette = Palette::new([10, 30, 10]).with(true, [120, 220, 90]);
+    let mut recorder = Recorder::new(Day14::DAY, "robots");
+    let mut visualizer = Visualizer::new(Day14::DAY, "robots", Palette::new([40, 60, 40]).with('#', [120, 220, 90]));
     let mut result = 0;
+    let mut pos = BitGrid::new(width as usize, height as usize);
     'outer: loop {
-        let mut pos = HashSet::new();
-
-        for robot in robots.clone() {
-            let x = robot.position.0 + robot.velocity.0 * result;
-            let y = robot.position.1 + robot.velocity.1 * result;
-            let p = (
-                ((x % boundary.0) + boundary.0) % boundary.0,
-                ((y % boundary.1) + boundary.1) % boundary.1,
-            );
-            if !pos.contains(&p) {
-                pos.insert(p);
-            } else {
+        pos.clear();
+
+        for robot in robots {
+            let p = robot.position.wrapping_add(robot.velocity * result, boundary);
+            if !pos.insert(p.position().expect("wrapped into the room")) {
                 break;
             }
         }
 
-        println!("POS: {} in res: {}", pos.len(), result);
+        recorder.frame(|| render::render_points(pos.iter().map(Point::from_position), width as usize, height as usize, &palette));
+        visualizer.frame(|| Scene::new(room(&pos)));
+        log::trace!("POS: {} in res: {}", pos.len(), result);
         // 4 hours to get to this point :...(
         // I tried all sorts of shit, including rendering every frame as an image to create an animation
         if pos.len() == robots.len() {
-            for y in 0..boundary.1 {
-                for x in 0..boundary.0 {
-                    if pos.contains(&(x, y)) {
-                        print!("#");
-                    } else {
-                        print!(".");
-                    }
-                }
-                println!();
-            }
-            println!("Iteration {}", result);
+            log::debug!("Iteration {}\n{}", result, room(&pos));
             break 'outer;
         }
 
         result += 1;
     }
 
-    println!("Day 14, part 2 result: {:?}", result);
-
     result
 }
 
-fn parse(input: &str) -> Vec<Robot> {
-    let re = Regex::new(r"p=(?P<x>-?\d+),(?P<y>-?\d+) v=(?P<dx>-?\d+),(?P<dy>-?\d+)").unwrap();
-    let mut robots = vec![];
-
-    for captures in re.captures_iter(input) {
-        let x = captures.name("x").unwrap().as_str().parse::<i32>().unwrap();
-        let y = captures.name("y").unwrap().as_str().parse::<i32>().unwrap();
-        let position = (x, y);
+fn parse_room(input: &str) -> Result<Room, ParseError> {
+    let (settings, robots) = parsing::settings(input, &["width", "height"])?;
+    Ok(Room {
+        width: settings.number("width", 101)?,
+        height: settings.number("height", 103)?,
+        robots: parse(input, robots)?,
+    })
+}
 
-        let dx = captures.name("dx").unwrap().as_str().parse::<i32>().unwrap();
-        let dy = captures.name("dy").unwrap().as_str().parse::<i32>().unwrap();
-        let velocity = (dx, dy);
+fn parse(input: &str, robots: &str) -> Result<Vec<Robot>, ParseError> {
+    robots
+        .lines()
+        .map(|line| {
+            let [x, y, dx, dy] = parsing::signed_ints_n(input, line, "`p=<x>,<y> v=<dx>,<dy>`")?;
+            Ok(Robot { position: Point::new(x, y), velocity: Vec2::new(dx, dy) })
+        })
+        .collect()
+}
 
-        robots.push(Robot { position, velocity });
+/// The room with a `#` for every robot.
+fn room(robots: &BitGrid) -> Grid<char> {
+    let mut grid = Grid::filled(robots.width(), robots.height(), '.');
+    for position in robots.iter() {
+        grid[position] = '#';
     }
-
-    robots
+    grid
 }
 
 #[cfg(test)]
-mod day14 {
+mod tests {
     use crate::day14::*;
     use std::assert_eq;
 
@@ -161,6 +169,15 @@ p=2,4 v=2,-3
 p=9,5 v=-3,-3";
     #[test]
     fn test1() {
-        assert_eq!(part_one(SAMPLE, 10, 6), 12)
+        assert_eq!(part_one(&parse(SAMPLE, SAMPLE).unwrap(), 10, 6), 12)
+    }
+
+    #[test]
+    fn test_parse_room() {
+        let room = parse_room(&format!("@width 11\n@height 7\n{}", SAMPLE)).unwrap();
+        assert_eq!((room.width, room.height, room.robots.len()), (11, 7, 12));
+
+        let room = parse_room(SAMPLE).unwrap();
+        assert_eq!((room.width, room.height), (101, 103));
     }
 }
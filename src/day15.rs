@@ -1,10 +1,17 @@
-use std::error::Error;
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::ParseError;
+use crate::render::terminal::{Scene, Visualizer};
+use crate::render::{self, Image, Palette, Recorder};
+use crate::solution::Solution;
+use crate::utils::{parsing, Direction, Grid, Position};
 
-type Warehouse = Vec<Vec<Tile>>;
+type Warehouse = Grid<Tile>;
+type Instructions = (Warehouse, Position, Vec<Direction>);
 
-#[derive(Debug, Clone, Copy)]
-enum Tile {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tile {
     Wall,
     Empty,
     Crate,
@@ -14,79 +21,73 @@ enum Tile {
 
 use Tile::*;
 
-#[derive(Debug, Clone, Copy)]
-enum Move {
-    Up,
-    Down,
-    Left,
-    Right,
+impl Tile {
+    fn symbol(self) -> char {
+        match self {
+            Wall => '#',
+            Crate => 'O',
+            Empty => '.',
+            BigCrateLeft => '[',
+            BigCrateRight => ']',
+        }
+    }
 }
 
-use Move::*;
-
-fn get_input(input: &str) -> (Warehouse, (usize, usize), Vec<Move>) {
-    let mut robot_pos = (0, 0);
+fn get_input(input: &str) -> Result<Instructions, ParseError> {
+    let (raw_warehouse, raw_movements) = parsing::two_blocks(input, "the moves")?;
 
-    let mut parts = input.trim().split("\n\n");
-    let raw_warehouse = parts.next().unwrap();
-    let raw_movements = parts.next().unwrap();
-
-    let warehouse = raw_warehouse
-        .trim()
-        .lines()
-        .enumerate()
-        .map(|(line_no, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(col_no, c)| match c {
-                    '#' => Wall,
-                    '.' => Empty,
-                    'O' => Crate,
-                    '@' => {
-                        robot_pos = (line_no, col_no);
-                        Empty
-                    }
-                    _ => unreachable!(),
-                })
-                .collect()
-        })
-        .collect();
+    let tiles = Grid::parse(raw_warehouse, "one of `#.O@`", |c| "#.O@".contains(c).then_some(c))?;
+    check_walls(input, raw_warehouse)?;
+    let robot_pos = tiles.find(|&c| c == '@').ok_or_else(|| ParseError::after(input, raw_warehouse, "a robot `@`"))?;
+    let warehouse = tiles.map(|&c| match c {
+        '#' => Wall,
+        'O' => Crate,
+        _ => Empty,
+    });
 
     let movements = raw_movements
         .lines()
         .flat_map(|line| {
-            line.chars()
-                .map(|e| match e {
-                    '<' => Left,
-                    '^' => Up,
-                    'v' => Down,
-                    '>' => Right,
-                    _ => unreachable!(),
+            line.char_indices()
+                .map(|(index, e)| {
+                    Direction::from_arrow(e)
+                        .ok_or_else(|| ParseError::at(input, &line[index..index + e.len_utf8()], "one of `<^v>`"))
                 })
-                .collect::<Vec<Move>>()
+                .collect::<Vec<_>>()
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
+
+    Ok((warehouse, robot_pos, movements))
+}
+
+/// Checks that the outermost tiles of the warehouse are all walls, so nothing is pushed off it.
+fn check_walls(input: &str, raw_warehouse: &str) -> Result<(), ParseError> {
+    let lines: Vec<&str> = raw_warehouse.lines().collect();
+    for (row, line) in lines.iter().enumerate() {
+        let border = if row == 0 || row == lines.len() - 1 { 0..line.len() } else { 0..1 };
+        let gap = border.chain(line.len().saturating_sub(1)..line.len()).find(|&index| &line[index..index + 1] != "#");
+        if let Some(index) = gap {
+            return Err(ParseError::at(input, &line[index..index + 1], "a wall `#` around the warehouse"));
+        }
+    }
 
-    (warehouse, robot_pos, movements)
+    Ok(())
 }
 
-fn try_move(movement: &Move, position: (usize, usize), warehouse: &mut Warehouse) -> bool {
-    let neighbor_pos = match movement {
-        Up => (position.0 - 1, position.1),
-        Down => (position.0 + 1, position.1),
-        Left => (position.0, position.1 - 1),
-        Right => (position.0, position.1 + 1),
+fn try_move(movement: Direction, position: Position, warehouse: &mut Warehouse) -> bool {
+    let Some(neighbor_pos) = warehouse.step(position, movement) else {
+        return false;
     };
-    match warehouse[neighbor_pos.0][neighbor_pos.1] {
+    match warehouse[neighbor_pos] {
         Empty => {
-            warehouse[neighbor_pos.0][neighbor_pos.1] = warehouse[position.0][position.1];
-            warehouse[position.0][position.1] = Empty;
+            warehouse[neighbor_pos] = warehouse[position];
+            warehouse[position] = Empty;
             true
         }
         Crate => {
             if try_move(movement, neighbor_pos, warehouse) {
-                warehouse[neighbor_pos.0][neighbor_pos.1] = warehouse[position.0][position.1];
-                warehouse[position.0][position.1] = Empty;
+                warehouse[neighbor_pos] = warehouse[position];
+                warehouse[position] = Empty;
                 true
             } else {
                 false
@@ -97,99 +98,87 @@ fn try_move(movement: &Move, position: (usize, usize), warehouse: &mut Warehouse
     }
 }
 
-fn display(warehouse: &Warehouse, robot: (usize, usize)) {
-    for (line_no, line) in warehouse.iter().enumerate() {
-        for (col_no, elem) in line.iter().enumerate() {
-            if (line_no, col_no) == robot {
-                print!("@");
-            } else {
-                print!(
-                    "{}",
-                    match elem {
-                        Wall => '#',
-                        Crate => 'O',
-                        Empty => '.',
-                        BigCrateLeft => '[',
-                        BigCrateRight => ']',
-                    }
-                );
-            }
-        }
-        println!();
-    }
+fn scene(warehouse: &Warehouse, robot: Position) -> Scene {
+    let mut grid = warehouse.map(|tile| tile.symbol());
+    grid[robot] = '@';
+    Scene::new(grid).with_actor(robot)
+}
+
+fn visualizer(name: &str) -> Visualizer {
+    let symbols = Palette::new([70, 70, 70])
+        .with('#', [150, 150, 150])
+        .with('O', [220, 150, 70])
+        .with('[', [220, 150, 70])
+        .with(']', [220, 150, 70]);
+    Visualizer::new(Day15::DAY, name, symbols).every(4)
+}
+
+fn picture(warehouse: &Warehouse, robot: Position) -> Image {
+    let palette = Palette::new([25, 25, 25])
+        .with(Wall, [120, 120, 120])
+        .with(Crate, [190, 130, 60])
+        .with(BigCrateLeft, [190, 130, 60])
+        .with(BigCrateRight, [160, 105, 45]);
+    let mut image = render::render(warehouse, &palette);
+    image[robot] = [240, 60, 60];
+    image
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> Result<(), Box<dyn Error + 'static>> {
-    let (mut warehouse, mut position, movements) = get_input(input);
-
-    for movement in movements {
-        if try_move(&movement, position, &mut warehouse) {
-            position = match movement {
-                Up => (position.0 - 1, position.1),
-                Down => (position.0 + 1, position.1),
-                Left => (position.0, position.1 - 1),
-                Right => (position.0, position.1 + 1),
-            };
+pub fn part_one(mut warehouse: Warehouse, mut position: Position, movements: &[Direction]) -> usize {
+    let mut recorder = Recorder::new(Day15::DAY, "warehouse").every(4);
+    let mut visualizer = visualizer("warehouse");
+    for &movement in movements {
+        if let Some(next) = warehouse.step(position, movement) {
+            if try_move(movement, position, &mut warehouse) {
+                position = next;
+            }
         }
+        recorder.frame(|| picture(&warehouse, position));
+        visualizer.frame(|| scene(&warehouse, position));
     }
 
     let mut gps = 0;
 
-    for (line_no, line) in warehouse.iter().enumerate() {
-        for (col_no, elem) in line.iter().enumerate() {
-            if let Crate = elem {
-                gps += 100 * line_no + col_no
-            }
+    for ((line_no, col_no), elem) in warehouse.iter() {
+        if let Crate = elem {
+            gps += 100 * line_no + col_no
         }
     }
 
-    println!("Day 15, part 1 result: {:?}", gps);
-
-    Ok(())
+    gps
 }
 
 fn enlarge_warehouse(small_warehouse: Warehouse) -> Warehouse {
-    let mut larger_warehouse = vec![];
-    for line in small_warehouse {
-        let mut larger_line = vec![];
-        for tile in line {
-            if let Crate = tile {
-                larger_line.push(BigCrateLeft);
-                larger_line.push(BigCrateRight);
-            } else {
-                larger_line.push(tile);
-                larger_line.push(tile);
-            }
-        }
-        larger_warehouse.push(larger_line);
-    }
-    larger_warehouse
+    let larger_rows = small_warehouse.rows().map(|line| {
+        line.iter()
+            .flat_map(|&tile| if let Crate = tile { [BigCrateLeft, BigCrateRight] } else { [tile, tile] })
+            .collect()
+    }).collect();
+
+    Grid::from_rows(larger_rows)
 }
 
 fn try_move_larger_crates(
-    movement: &Move,
-    position: (usize, usize),
+    movement: Direction,
+    position: Position,
     warehouse: &mut Warehouse,
     apply: bool,
 ) -> bool {
-    let neighbor_pos = match movement {
-        Up => (position.0 - 1, position.1),
-        Down => (position.0 + 1, position.1),
-        Left => (position.0, position.1 - 1),
-        Right => (position.0, position.1 + 1),
+    let Some(neighbor_pos) = warehouse.step(position, movement) else {
+        return false;
     };
-    match warehouse[neighbor_pos.0][neighbor_pos.1] {
+    match warehouse[neighbor_pos] {
         Empty => {
             if apply {
-                warehouse[neighbor_pos.0][neighbor_pos.1] = warehouse[position.0][position.1];
-                warehouse[position.0][position.1] = Empty;
+                warehouse[neighbor_pos] = warehouse[position];
+                warehouse[position] = Empty;
             }
             true
         }
         Wall => false,
         BigCrateLeft => match movement {
-            Up | Down => {
+            Direction::Up | Direction::Down => {
                 if try_move_larger_crates(movement, neighbor_pos, warehouse, apply)
                     & try_move_larger_crates(
                     movement,
@@ -199,9 +188,9 @@ fn try_move_larger_crates(
                 )
                 {
                     if apply {
-                        warehouse[neighbor_pos.0][neighbor_pos.1] =
-                            warehouse[position.0][position.1];
-                        warehouse[position.0][position.1] = Empty;
+                        warehouse[neighbor_pos] =
+                            warehouse[position];
+                        warehouse[position] = Empty;
                     }
                     true
                 } else {
@@ -211,9 +200,9 @@ fn try_move_larger_crates(
             _ => {
                 if try_move_larger_crates(movement, neighbor_pos, warehouse, apply) {
                     if apply {
-                        warehouse[neighbor_pos.0][neighbor_pos.1] =
-                            warehouse[position.0][position.1];
-                        warehouse[position.0][position.1] = Empty;
+                        warehouse[neighbor_pos] =
+                            warehouse[position];
+                        warehouse[position] = Empty;
                     }
                     true
                 } else {
@@ -222,7 +211,7 @@ fn try_move_larger_crates(
             }
         },
         BigCrateRight => match movement {
-            Up | Down => {
+            Direction::Up | Direction::Down => {
                 if try_move_larger_crates(movement, neighbor_pos, warehouse, apply)
                     & try_move_larger_crates(
                     movement,
@@ -232,9 +221,9 @@ fn try_move_larger_crates(
                 )
                 {
                     if apply {
-                        warehouse[neighbor_pos.0][neighbor_pos.1] =
-                            warehouse[position.0][position.1];
-                        warehouse[position.0][position.1] = Empty;
+                        warehouse[neighbor_pos] =
+                            warehouse[position];
+                        warehouse[position] = Empty;
                     }
                     true
                 } else {
@@ -244,9 +233,9 @@ fn try_move_larger_crates(
             _ => {
                 if try_move_larger_crates(movement, neighbor_pos, warehouse, apply) {
                     if apply {
-                        warehouse[neighbor_pos.0][neighbor_pos.1] =
-                            warehouse[position.0][position.1];
-                        warehouse[position.0][position.1] = Empty;
+                        warehouse[neighbor_pos] =
+                            warehouse[position];
+                        warehouse[position] = Empty;
                     }
                     true
                 } else {
@@ -258,41 +247,70 @@ fn try_move_larger_crates(
     }
 }
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    const NAME: &'static str = "Warehouse Woes";
+
+    type Input<'a> = Instructions;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        get_input(input)
+    }
+
+    fn part_one((warehouse, position, movements): &Self::Input<'_>) -> Answer {
+        part_one(warehouse.clone(), *position, movements).into()
+    }
+
+    fn part_two((warehouse, position, movements): &Self::Input<'_>) -> Answer {
+        part_two(warehouse.clone(), *position, movements).into()
+    }
 }
 
 #[measure_time]
-fn part_two(input: &str) -> Result<(), Box<dyn Error + 'static>> {
-    let (warehouse, mut position, movements) = get_input(input);
+fn part_two(warehouse: Warehouse, mut position: Position, movements: &[Direction]) -> usize {
     let mut larger_warehouse = enlarge_warehouse(warehouse);
     position.1 *= 2;
 
-    for movement in movements {
-        if try_move_larger_crates(&movement, position, &mut larger_warehouse, false) {
-            try_move_larger_crates(&movement, position, &mut larger_warehouse, true);
-            position = match movement {
-                Up => (position.0 - 1, position.1),
-                Down => (position.0 + 1, position.1),
-                Left => (position.0, position.1 - 1),
-                Right => (position.0, position.1 + 1),
-            };
+    let mut recorder = Recorder::new(Day15::DAY, "wide-warehouse").every(4);
+    let mut visualizer = visualizer("wide-warehouse");
+    for &movement in movements {
+        if let Some(next) = larger_warehouse.step(position, movement) {
+            if try_move_larger_crates(movement, position, &mut larger_warehouse, false) {
+                try_move_larger_crates(movement, position, &mut larger_warehouse, true);
+                position = next;
+            }
         }
+        recorder.frame(|| picture(&larger_warehouse, position));
+        visualizer.frame(|| scene(&larger_warehouse, position));
     }
-    display(&larger_warehouse, position);
+    log::debug!("Warehouse after the last move:\n{}", scene(&larger_warehouse, position).grid);
 
     let mut gps = 0;
 
-    for (line_no, line) in larger_warehouse.iter().enumerate() {
-        for (col_no, elem) in line.iter().enumerate() {
-            if let BigCrateLeft = elem {
-                gps += 100 * line_no + col_no
-            }
+    for ((line_no, col_no), elem) in larger_warehouse.iter() {
+        if let BigCrateLeft = elem {
+            gps += 100 * line_no + col_no
         }
     }
 
-    println!("Day 15, part 2 result: {:?}", gps);
+    gps
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use crate::day15::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_get_input_walls() {
+        let (warehouse, robot, movements) = get_input("####\n#@O#\n####\n\n<>").unwrap();
+        assert_eq!((warehouse.width(), robot, movements.len()), (4, (1, 1), 2));
+
+        let error = get_input("####\n#@O.\n####\n\n<>").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 4, "a wall `#` around the warehouse"));
+        let error = get_input("#.##\n#@O#\n####\n\n<>").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+    }
 }
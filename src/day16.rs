@@ -1,187 +1,95 @@
-use itertools::Itertools;
-use std::collections::{BinaryHeap, HashMap};
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::render::terminal::{Scene, Visualizer};
+use crate::render::Palette;
+use crate::solution::Solution;
+use crate::utils::graph::{self, Graph};
+use crate::utils::{BitGrid, Direction, Grid, Position, StateSet};
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
-}
-
-#[measure_time]
-pub fn part_one(input: &str) -> i128 {
-    let g: Vec<Vec<_>> = input.lines().map(|l| l.bytes().collect()).collect();
-    let (sy, sx) = (0..g.len())
-        .flat_map(|y| (0..g[0].len()).map(move |x| (y, x)))
-        .find(|&(y, x)| g[y][x] == b'S')
-        .expect("starting point exists");
-
-    let mut queue = BinaryHeap::from_iter([(0, 0, sy as i32, sx as i32)]);
-    let mut visited = HashMap::new();
+pub struct Day16;
 
-    let mut result = i128::MAX;
-
-    let (ry, rx) = (0..g.len() as i32, 0..g[0].len() as i32);
-    while let Some((score, dir, cy, cx)) = queue.pop() {
-        if visited.get(&(dir, cy, cx)).map_or(false, |&s| s <= -score) {
-            continue;
-        }
-        visited.insert((dir, cy, cx), -score);
-        if g[cy as usize][cx as usize] == b'E' {
-            result = result.min(-score)
-        }
-        // move next
-        let (dy, dx) = [(0, -1), (-1, 0), (0, 1), (1, 0)][dir];
-        let (ny, nx) = (cy + dy, cx + dx);
-        if ry.contains(&ny) && rx.contains(&nx) && g[ny as usize][nx as usize] != b'#' {
-            queue.push((score - 1i128, dir, ny, nx));
-        }
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+    const NAME: &'static str = "Reindeer Maze";
 
-        queue.push((score - 1000i128, (dir + 3) % 4, cy, cx));
+    type Input<'a> = Grid<u8>;
 
-        queue.push((score - 1000i128, (dir + 1) % 4, cy, cx));
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_maze(input)
     }
 
-    println!("Day 16, part 1 result: {:?}", result);
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
 
-    result
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
-#[measure_time]
-pub fn part_two(s: &str) -> i128 {
-    let width = s.chars().position(|c| c == '\n').unwrap();
-    let mut end = 0;
-    let mut start = 0;
-    let map: Vec<_> = s
-        .chars()
-        .filter(|&c| c != '\n')
-        .enumerate()
-        .map(|(n, c)| match c {
-            '#' => true,
-            '.' => false,
-            'E' => {
-                end = n as i16;
-                false
-            }
-            'S' => {
-                start = n as i16;
-                false
-            }
-            _ => panic!("Invalid character in input"),
-        })
-        .collect();
-
-    let mut visited = vec![vec![None::<(u32, Vec<(i16, i8)>)>; 4]; map.len()];
-    let mut queue = BinaryHeap::new();
-    queue.push((std::cmp::Reverse(0), start, 0i8, None));
-    queue.extend(
-        (1..)
-            .map(|steps| {
-                (
-                    std::cmp::Reverse(steps),
-                    start + steps as i16,
-                    0i8,
-                    Some((start + steps as i16 - 1, 0i8)),
-                )
-            })
-            .take_while(|&(_, pos, _, _)| !map[pos as usize]),
-    );
-
-    let mut end_cost = u32::MAX;
-    let mut end_positions = Vec::new();
-
-    while let Some((std::cmp::Reverse(cost), pos, direction, came_from)) = queue.pop() {
-        if cost > end_cost {
-            break;
+fn parse_maze(input: &str) -> Result<Grid<u8>, ParseError> {
+    for tile in ['S', 'E'] {
+        if !input.contains(tile) {
+            return Err(ParseError::end(input, format!("a `{}` tile", tile)));
         }
+    }
 
-        match &mut visited[pos as usize][direction as usize] {
-            Some((visited_cost, visited_came_from)) => {
-                if cost == *visited_cost {
-                    if let Some(came_from) = came_from {
-                        if !visited_came_from.contains(&came_from) {
-                            visited_came_from.push(came_from);
-                        }
-                    }
-                }
-                continue;
-            }
-            x @ None => {
-                *x = Some((
-                    cost,
-                    if let Some(came_from) = came_from {
-                        vec![came_from]
-                    } else {
-                        Vec::new()
-                    },
-                ));
-            }
-        }
+    Grid::parse(input, "one of `#.SE`", |tile| "#.SE".contains(tile).then_some(tile as u8))
+}
 
-        if pos == end {
-            end_cost = cost;
-            end_positions.push((pos, direction));
-            continue;
-        }
+/// The maze as a graph of states, a position and the direction the reindeer faces. Stepping
+/// forward costs 1 and turning costs 1000.
+fn reindeer_moves(g: &Grid<u8>) -> impl Graph<Node = (Position, Direction)> + '_ {
+    let moves = graph::from_fn(|(position, direction): (Position, Direction)| {
+        let forward = g.step(position, direction).filter(|&next| g[next] != b'#').map(|next| ((next, direction), 1));
+        let turns = [direction.turn_left(), direction.turn_right()].map(|turned| ((position, turned), 1000));
+        forward.into_iter().chain(turns)
+    });
+    graph::with_node_set(moves, || StateSet::for_grid(g, Direction::ALL.len()))
+}
 
-        queue.extend(
-            [(direction - 1).rem_euclid(4), (direction + 1).rem_euclid(4)]
-                .into_iter()
-                .flat_map(|new_direction| {
-                    let movement = match new_direction {
-                        0 => 1,
-                        1 => width as i16,
-                        2 => -1,
-                        3 => -(width as i16),
-                        _ => panic!("Invalid direction"),
-                    };
+#[measure_time]
+pub fn part_one(g: &Grid<u8>) -> i128 {
+    let start = g.find(|&tile| tile == b'S').expect("starting point exists");
 
-                    (1..)
-                        .map(move |steps| {
-                            (
-                                std::cmp::Reverse(cost + 1000 + steps),
-                                pos + movement * steps as i16,
-                                new_direction,
-                                Some((
-                                    pos + movement * (steps as i16 - 1),
-                                    if steps == 1 { direction } else { new_direction },
-                                )),
-                            )
-                        })
-                        .take_while(|&(_, new_pos, _, _)| !map[new_pos as usize])
-                }),
-        );
+    let best = reindeer_moves(g).shortest_path((start, Direction::Right), |(position, _)| g[position] == b'E');
+    if let Some(path) = &best {
+        walk(g, &path.nodes);
     }
 
-    let mut count = 0;
-    let mut checked = vec![[false; 4]; map.len()];
-    let mut counted = vec![false; map.len()];
-    let mut to_check = end_positions;
-
-    while let Some((pos, direction)) = to_check.pop() {
-        if checked[pos as usize][direction as usize] {
-            continue;
-        }
-
-        checked[pos as usize][direction as usize] = true;
+    best.map_or(i128::MAX, |path| path.cost as i128)
+}
 
-        if !counted[pos as usize] {
-            counted[pos as usize] = true;
-            count += 1;
-        }
+/// Shows the reindeer walking the maze along `path`, when visualizing.
+fn walk(g: &Grid<u8>, path: &[(Position, Direction)]) {
+    let symbols = Palette::new([90, 90, 100]).with('#', [150, 150, 150]).with('E', [90, 220, 90]);
+    let mut visualizer = Visualizer::new(Day16::DAY, "reindeer", symbols);
+    if !visualizer.is_visualizing() {
+        return;
+    }
 
-        to_check.extend(
-            visited[pos as usize][direction as usize]
-                .as_ref()
-                .unwrap()
-                .1
-                .iter()
-                .copied(),
-        );
+    let maze = g.map(|&tile| tile as char);
+    for &(position, direction) in path {
+        visualizer.frame(|| {
+            let mut grid = maze.clone();
+            grid[position] = direction.arrow();
+            Scene::new(grid).with_actor(position)
+        });
     }
+}
+
+#[measure_time]
+pub fn part_two(g: &Grid<u8>) -> i128 {
+    let start = g.find(|&tile| tile == b'S').expect("starting point exists");
+    let end = g.find(|&tile| tile == b'E').expect("end point exists");
 
-    println!("Day 16, part 1 result: {:?}", count);
+    let paths = reindeer_moves(g).all_shortest_paths((start, Direction::Right));
+    let best_ends = paths.cheapest(Direction::ALL.map(|direction| (end, direction)));
+    let mut tiles = BitGrid::for_grid(g);
+    tiles.extend(paths.covered(best_ends).into_iter().map(|(position, _)| position));
 
-    count
+    tiles.len() as i128
 }
 
 #[cfg(test)]
@@ -205,10 +113,8 @@ mod tests {
 #.###.#.#.#.#.#
 #S..#.....#...#
 ###############";
-        let fa = part_one(a);
+        let fa = part_one(&parse_maze(a).unwrap());
         assert_eq!(fa, 7036);
-        let c = part_one(include_str!("../data/day16.txt"));
-        println!("part1: {fa} {c}");
     }
 
     #[test]
@@ -228,9 +134,8 @@ mod tests {
 #.###.#.#.#.#.#
 #S..#.....#...#
 ###############";
-        let fa = part_two(a);
+        let fa = part_two(&parse_maze(a).unwrap());
         assert_eq!(fa, 45);
-        println!("part2: fa={fa}");
         let b = "#################
 #...#...#...#..E#
 #.#.#.#.#.#.#.#.#
@@ -248,10 +153,7 @@ mod tests {
 #.#.#.#########.#
 #S#.............#
 #################";
-        let fb = part_two(b);
+        let fb = part_two(&parse_maze(b).unwrap());
         assert_eq!(fb, 64);
-        println!("part2: fb={fb}");
-        let c = part_two(include_str!("../data/day16.txt"));
-        println!("part2: {fa} {c}");
     }
 }
@@ -3,22 +3,13 @@ use std::collections::BinaryHeap;
 use std::ops::BitXor;
 use itertools::Itertools;
 use measure_time_macro::measure_time;
-use regex::Regex;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
 #[derive(Debug, Clone)]
-enum Instruction {
-    Adv = 0,
-    Bxl = 1,
-    Bst = 2,
-    Jnz = 3,
-    Bxc = 4,
-    Out = 5,
-    Bdv = 6,
-    Cdv = 7,
-}
-
-#[derive(Debug, Clone)]
-struct Computer {
+pub struct Computer {
     register_a: i128,
     register_b: i128,
     register_c: i128,
@@ -36,25 +27,25 @@ impl Computer {
         }
     }
 
-    fn adv(&mut self, operand: i8) -> () {
+    fn adv(&mut self, operand: i8) {
         self.register_a = (self.register_a as f64 / 2_i32.pow(self.combo_operand(operand) as u32) as f64).floor() as i128
     }
 
-    fn bxl(&mut self, operand: i8) -> () {
+    fn bxl(&mut self, operand: i8) {
         self.register_b = (self.register_b as i8).bitxor(operand) as i128
     }
 
-    fn bst(&mut self, operand: i8) -> () {
+    fn bst(&mut self, operand: i8) {
         self.register_b = self.combo_operand(operand) & 7
     }
 
-    fn jnz(&mut self, operand: i8) -> () {
+    fn jnz(&mut self, operand: i8) {
         if self.register_a != 0 {
             self.instruction_pointer = operand as usize
         }
     }
 
-    fn bxc(&mut self, _operand: i8) -> () {
+    fn bxc(&mut self, _operand: i8) {
         self.register_b = self.register_b.bitxor(self.register_c)
     }
 
@@ -62,19 +53,20 @@ impl Computer {
         self.combo_operand(operand) & 7
     }
 
-    fn bdv(&mut self, operand: i8) -> () {
+    fn bdv(&mut self, operand: i8) {
         self.register_b = (self.register_a as f64 / 2_i32.pow(self.combo_operand(operand) as u32) as f64).floor() as i128
     }
 
-    fn cdv(&mut self, operand: i8) -> () {
+    fn cdv(&mut self, operand: i8) {
         self.register_c = (self.register_a as f64 / 2_i32.pow(self.combo_operand(operand) as u32) as f64).floor() as i128
     }
 
-    pub fn reset(&mut self, register_a: i128) -> () {
+    pub fn reset(&mut self, register_a: i128) {
         self.register_a = register_a;
         self.instruction_pointer = 0;
     }
 
+    #[measure_time]
     pub fn run_program(&mut self) -> String {
         let mut result: Vec<i128> = Vec::new();
         loop {
@@ -88,7 +80,7 @@ impl Computer {
 
             let [opcode, operand] = [self.program[self.instruction_pointer], self.program[self.instruction_pointer + 1]];
 
-            println!("{:?}, RES: {:?}, Program TICK: instr: {}, opcode: {}, operand: {}, registers: [{}, {}, {}] ", self.program, result, self.instruction_pointer, opcode, operand, self.register_a, self.register_b, self.register_c );
+            log::trace!("{:?}, RES: {:?}, Program TICK: instr: {}, opcode: {}, operand: {}, registers: [{}, {}, {}] ", self.program, result, self.instruction_pointer, opcode, operand, self.register_a, self.register_b, self.register_c );
 
             self.instruction_pointer += 2;
             match opcode {
@@ -109,50 +101,67 @@ impl Computer {
     }
 }
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
-    // 48744869 low
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+    const NAME: &'static str = "Chronospatial Computer";
+
+    type Input<'a> = Computer;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> String {
-    let mut computer: Computer = parse_input(input);
+pub fn part_one(computer: &Computer) -> String {
+    let mut computer: Computer = computer.clone();
     let result: String = computer.run_program();
 
-    println!("Day 17, part 1 result: {:?}", result.clone());
-
     result
 }
 
-fn parse_input(input: &str) -> Computer {
-    let re = Regex::new(r"Register A: (?P<register_a>\d+)
-Register B: (?P<register_b>\d+)
-Register C: (?P<register_c>\d+)
-
-Program: (?P<program>[\d,]+)").unwrap();
-
-    if let Some(captures) = re.captures(input) {
-        let register_a = captures.name("register_a").unwrap().as_str().parse::<i128>().unwrap();
-        let register_b = captures.name("register_b").unwrap().as_str().parse::<i128>().unwrap();
-        let register_c = captures.name("register_c").unwrap().as_str().parse::<i128>().unwrap();
-        let program: Vec<i8> = captures
-            .name("program")
-            .unwrap()
-            .as_str()
-            .split(',')
-            .map(|x| x.parse::<i8>().unwrap())
-            .collect();
-
-        Computer { register_a, register_b, register_c, program, instruction_pointer: 0 }
-    } else {
-        panic!()
-    }
+fn parse_input(input: &str) -> Result<Computer, ParseError> {
+    let mut lines = input.lines();
+    let mut registers = [0; 3];
+
+    for (register, name) in registers.iter_mut().zip(['A', 'B', 'C']) {
+        let prefix = format!("Register {}: ", name);
+        let expected = format!("`{}<n>`", prefix);
+        let line = lines.next().ok_or_else(|| ParseError::end(input, &expected))?;
+        let value = line.strip_prefix(&prefix).ok_or_else(|| ParseError::at(input, line, &expected))?;
+        *register = parse::number::<i128>(input, value)?;
+    }
+
+    let line = lines.find(|line| !line.is_empty()).ok_or_else(|| ParseError::end(input, "`Program: <opcodes>`"))?;
+    let opcodes = line.strip_prefix("Program: ").ok_or_else(|| ParseError::at(input, line, "`Program: <opcodes>`"))?;
+    let program: Vec<i8> = opcodes
+        .split(',')
+        .map(|x| match parse::number::<i8>(input, x)? {
+            value @ 0..=7 => Ok(value),
+            _ => Err(ParseError::at(input, x, "a 3-bit number")),
+        })
+        .collect::<Result<_, _>>()?;
+    if !program.len().is_multiple_of(2) {
+        return Err(ParseError::after(input, line, "an operand"));
+    }
+
+    let [register_a, register_b, register_c] = registers;
+    Ok(Computer { register_a, register_b, register_c, program, instruction_pointer: 0 })
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> u64 {
-    let mut computer: Computer = parse_input(input);
+pub fn part_two(computer: &Computer) -> u64 {
+    let mut computer: Computer = computer.clone();
     let mut candidates: BinaryHeap<Reverse<u64>> = BinaryHeap::new();
     let target_program = computer.program.iter().map(|x| x.to_string()).join(",");
 
@@ -162,14 +171,13 @@ pub fn part_two(input: &str) -> u64 {
     }
 
     while let Some(Reverse(candidate)) = candidates.pop() {
-        println!("Candidate: {}", candidate);
+        log::debug!("Candidate: {}", candidate);
 
         computer.reset(candidate as i128);
 
         let output = computer.run_program();
 
         if output == target_program {
-            println!("Day 17, part 2 result: {}", candidate);
             return candidate;
         }
 
@@ -182,21 +190,11 @@ pub fn part_two(input: &str) -> u64 {
         }
     }
 
-    println!("Day 17, part 2 result: No result");
     0
 }
 
-fn simulate_loop(a: usize) -> usize {
-    let mut b = a % 8;
-    b = b ^ 3;
-    let c = a >> b;
-    b = b ^ c;
-    b = b ^ 3;
-    b % 8
-}
-
 #[cfg(test)]
-mod day17 {
+mod tests {
     use crate::day17::*;
     use std::assert_eq;
 
@@ -208,7 +206,7 @@ Program: 0,1,5,4,3,0";
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1), "4,6,3,5,6,3,5,2,1,0")
+        assert_eq!(part_one(&parse_input(TEST_CASE_1).unwrap()), "4,6,3,5,6,3,5,2,1,0")
     }
 
     const TEST_CASE_2: &str = "Register A: 2024
@@ -219,6 +217,6 @@ Program: 0,3,5,4,3,0";
 
     #[test]
     fn test_part_one_case_two() {
-        assert_eq!(part_two(TEST_CASE_2), 117440)
+        assert_eq!(part_two(&parse_input(TEST_CASE_2).unwrap()), 117440)
     }
 }
@@ -1,170 +1,103 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use itertools::Itertools;
 use measure_time_macro::measure_time;
-
-pub fn run(input: &str) {
-    part_one(input, 71, 1024);
-    part_two(input, 71, 1024);
-}
-
-#[derive(Debug, Clone)]
-struct Node {
-    x: usize,
-    y: usize,
-    value: i8,
-}
-
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+use crate::utils::graph::{self, Graph};
+use crate::utils::{parsing, Grid, Point, Position};
+
+/// The bytes falling into a `size` x `size` memory, of which the first `time` have fallen before
+/// part one; 71x71 and 1024 unless the input sets `@size` and `@bytes`.
 #[derive(Debug, Clone)]
-struct Graph {
-    edges: HashMap<usize, HashSet<usize>>,
-    nodes: HashMap<usize, Node>,
+pub struct FallingBytes {
+    size: usize,
+    time: usize,
+    walls: Vec<Point>,
 }
 
-impl Graph {
-
-    fn new() -> Self {
-        Self {
-            edges: HashMap::new(),
-            nodes: HashMap::new(),
-        }
-    }
+pub struct Day18;
 
-    pub fn add_node(&mut self, coords: (usize, usize), value: i8) -> usize {
-        let current_id = self.nodes.len();
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+    const NAME: &'static str = "RAM Run";
 
-        self.nodes.insert(current_id, Node { x: coords.0, y: coords.1, value });
+    type Input<'a> = FallingBytes;
 
-        current_id
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_bytes(input)
     }
 
-    pub fn add_edge(&mut self, from_id: usize, to_id: usize) {
-        self.edges.entry(from_id).or_insert_with(HashSet::new).insert(to_id);
-        self.edges.entry(to_id).or_insert_with(HashSet::new).insert(from_id);
+    fn part_one(bytes: &Self::Input<'_>) -> Answer {
+        part_one(&bytes.walls, bytes.size, bytes.time).into()
     }
 
-    pub fn dijkstra(&self, start: usize, end: usize) -> usize {
-        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
-        let mut distance: Vec<usize> = vec![usize::MAX; self.nodes.len()];
-        let mut previous: Vec<usize>  = vec![0; self.nodes.len()];
-
-        queue.push_back((start, 0));
-
-        loop {
-            if let Some((current_node, current_distance)) = queue.pop_front() {
-
-                if current_node == end {
-                    break;
-                }
-
-                if let Some(neighbors) = self.edges.get(&current_node) {
-                    for &neighbour in neighbors {
-                        let new_distance = current_distance + 1usize;
-
-                        if new_distance < *distance.get(neighbour).expect("All distances are here") {
-                            distance[neighbour] = new_distance;
-                            previous[neighbour] = current_node;
-                            queue.push_back((neighbour, new_distance));
-                        }
-                    }
-                }
-            } else {
-               break;
-            }
+    fn part_two(bytes: &Self::Input<'_>) -> Answer {
+        match part_two(&bytes.walls, bytes.size) {
+            Some(wall) => format!("{},{}", wall.x, wall.y).into(),
+            None => "never blocked".into(),
         }
-
-        distance.get(end).expect("Must be end in costs").clone()
     }
 }
 
+/// Steps from the top left to the bottom right corner, `usize::MAX` when the way is blocked.
 #[measure_time]
-pub fn part_one(input: &str, size: usize, time: usize) -> usize {
-    let walls: Vec<(usize, usize)> = input.lines().take(time).enumerate().map(|(y, line)| {
-        let split: Vec<usize> = line.split(",").map(|x| x.parse::<usize>().expect("There are only numbers here")).collect();
-        (split.get(0).expect("Must be x").clone(), split.get(1).expect("Must be y").clone())
-    }).collect();
-
-    let graph: Graph = parse_input(walls, size);
-
-    let result = graph.dijkstra(0, size.pow(2) - 1);
+fn escape_steps(memory: &Grid<bool>) -> usize {
+    let graph = graph::from_fn(|position| {
+        memory.neighbours4(position).filter(|&neighbour| !memory[neighbour]).map(|neighbour| (neighbour, 1))
+    });
+    let exit = (memory.height() - 1, memory.width() - 1);
+    let distance_to_exit = |(row, col): Position| (row.abs_diff(exit.0) + col.abs_diff(exit.1)) as u64;
 
-    println!("Day 18, part 1 result: {:?}", result);
+    graph
+        .astar((0, 0), |position| position == exit, distance_to_exit)
+        .map_or(usize::MAX, |path| path.cost as usize)
+}
 
-    result
+fn parse_bytes(input: &str) -> Result<FallingBytes, ParseError> {
+    let (settings, walls) = parsing::settings(input, &["size", "bytes"])?;
+    Ok(FallingBytes {
+        size: settings.number("size", 71)?,
+        time: settings.number("bytes", 1024)?,
+        walls: parse_walls(input, walls)?,
+    })
 }
 
-fn parse_input(walls: Vec<(usize, usize)>, size: usize) -> Graph {
-    let mut node_ids: HashMap<(usize, usize), usize> = HashMap::new();
+fn parse_walls(input: &str, walls: &str) -> Result<Vec<Point>, ParseError> {
+    walls.lines().map(|line| {
+        let (x, y) = line.split_once(',').ok_or_else(|| ParseError::after(input, line, "`,`"))?;
+        Ok(Point::new(parse::number(input, x)?, parse::number(input, y)?))
+    }).collect()
+}
 
-    let mut graph: Graph = Graph::new();
+#[measure_time]
+pub fn part_one(walls: &[Point], size: usize, time: usize) -> usize {
+    let memory = parse_input(&walls[..time.min(walls.len())], size);
 
-    for i in 0..size {
-        for j in 0..size {
-            let val = if walls.contains(&(j, i)) { 0_i8 } else { 1_i8 };
-            let node_id = graph.add_node((j, i), val);
-            node_ids.insert((j, i), node_id);
-        }
-    }
+    escape_steps(&memory)
+}
 
-    for i in 0..size {
-        for j in 0..size {
-            if !walls.contains(&(j, i)) {
-                if let Some(&node_id) = node_ids.get(&(j, i)) {
-                    if let Some(&left_id) = node_ids.get(&(j.wrapping_sub(1), i)) {
-                        if !walls.contains(&(j.wrapping_sub(1), i)) {
-                            graph.add_edge(node_id, left_id);
-                        }
-                    }
-
-                    if let Some(&right_id) = node_ids.get(&(j + 1, i)) {
-                        if !walls.contains(&(j + 1, i)) {
-                            graph.add_edge(node_id, right_id);
-                        }
-                    }
-
-                    if let Some(&top_id) = node_ids.get(&(j, i.wrapping_sub(1))) {
-                        if !walls.contains(&(j, i.wrapping_sub(1))) {
-                            graph.add_edge(node_id, top_id);
-                        }
-                    }
-
-                    if let Some(&bottom_id) = node_ids.get(&(j, i + 1)) {
-                        if !walls.contains(&(j, i + 1)) {
-                            graph.add_edge(node_id, bottom_id);
-                        }
-                    }
-                }
-            }
+fn parse_input(walls: &[Point], size: usize) -> Grid<bool> {
+    let mut memory: Grid<bool> = Grid::filled(size, size, false);
+    for wall in walls {
+        if let Some(corrupted) = wall.position().and_then(|position| memory.get_mut(position)) {
+            *corrupted = true;
         }
     }
 
-    graph
+    memory
 }
 
+/// The first byte that cuts the exit off, `None` when the exit stays reachable after all of them.
 #[measure_time]
-pub fn part_two(input: &str, size: usize, time: usize) -> String {
-    let mut result = 0;
-    let walls: Vec<(usize, usize)> = input.lines().enumerate().map(|(y, line)| {
-        let split: Vec<usize> = line.split(",").map(|x| x.parse::<usize>().expect("There are only numbers here")).collect();
-        (split.get(0).expect("Must be x").clone(), split.get(1).expect("Must be y").clone())
-    }).collect();
-
-    for i in 0..walls.len() - 1 {
-        let graph: Graph = parse_input(walls.iter().take(time + i).cloned().collect(), size);
-
-        if graph.dijkstra(0, size.pow(2) - 1) == usize::MAX {
-            result = time + i - 1;
-            break;
-        }
-    }
-
-
-    println!("Day 18, part 2 result: {:?}", walls.get(result));
+pub fn part_two(walls: &[Point], size: usize) -> Option<Point> {
+    // Once the exit is cut off it stays that way, so the first blocking byte can be bisected.
+    let counts: Vec<usize> = (1..=walls.len()).collect();
+    let reachable = counts.partition_point(|&count| escape_steps(&parse_input(&walls[..count], size)) != usize::MAX);
 
-    walls.get(result).map(|(x, y)| [x.to_string(), y.to_string()].join(",")).take().expect("Must be the result")
+    walls.get(reachable).copied()
 }
 
 #[cfg(test)]
-mod day18 {
+mod tests {
     use crate::day18::*;
     use std::assert_eq;
 
@@ -196,11 +129,27 @@ mod day18 {
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1, 7, 12), 22)
+        assert_eq!(part_one(&parse_walls(TEST_CASE_1, TEST_CASE_1).unwrap(), 7, 12), 22)
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let bytes = parse_bytes(&format!("@size 7\n@bytes 12\n{}", TEST_CASE_1)).unwrap();
+        assert_eq!((bytes.size, bytes.time, bytes.walls.len()), (7, 12, 25));
+
+        let bytes = parse_bytes(TEST_CASE_1).unwrap();
+        assert_eq!((bytes.size, bytes.time), (71, 1024));
     }
 
     #[test]
     fn test_part_one_case_two() {
-        assert_eq!(part_two(TEST_CASE_1, 7, 12), "6,1")
+        assert_eq!(part_two(&parse_walls(TEST_CASE_1, TEST_CASE_1).unwrap(), 7), Some(Point::new(6, 1)))
+    }
+
+    #[test]
+    fn test_part_two_never_blocked() {
+        assert_eq!(part_two(&[], 7), None);
+        assert_eq!(part_two(&parse_walls(TEST_CASE_1, TEST_CASE_1).unwrap()[..12], 7), None);
+        assert_eq!(Day18::part_two(&parse_bytes("@size 7\n0,1").unwrap()), Answer::from("never blocked"));
     }
 }
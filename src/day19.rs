@@ -1,110 +1,115 @@
-use std::collections::HashMap;
-use itertools::Itertools;
-use measure_time_macro::measure_time;
+use measure_time_macro::{measure_time, memoize};
+use measure_time_runtime::memo;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::parsing;
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
-}
+pub struct Day19;
 
-#[measure_time]
-pub fn part_one(input: &str) -> i32 {
-    let (available_towels, desired_designs) = parse_input(input);
-    let mut result = 0;
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+    const NAME: &'static str = "Linen Layout";
 
-    for design in desired_designs {
-        if check_design(design, available_towels.clone()) { result += 1 }
+    type Input<'a> = (Vec<&'a str>, Vec<&'a str>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
     }
 
-    println!("Day 19, part 1 result: {:?}", result);
+    fn part_one((available_towels, desired_designs): &Self::Input<'_>) -> Answer {
+        part_one(available_towels, desired_designs).into()
+    }
 
-    result
+    fn part_two((available_towels, desired_designs): &Self::Input<'_>) -> Answer {
+        part_two(available_towels, desired_designs).into()
+    }
 }
 
-fn parse_input(input: &str) -> (Vec<&str>, Vec<&str>) {
-    let mut parts = input.trim().split("\n\n");
-    let raw_available_towels = parts.next().unwrap();
-    let raw_desired_designs = parts.next().unwrap();
+#[measure_time]
+pub fn part_one(available_towels: &[&str], desired_designs: &[&str]) -> i32 {
+    memo::scope(|| {
+        let mut result = 0;
 
-    let available_towels: Vec<&str> = raw_available_towels.split(",").map(|towel| towel.trim()).collect();
-    let desired_designs: Vec<&str> = raw_desired_designs.lines().map(|design| design.trim()).collect();
+        for design in desired_designs {
+            if check_design(design, available_towels) { result += 1 }
+        }
 
-    (available_towels, desired_designs)
+        result
+    })
 }
 
+fn parse_input(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    let (raw_available_towels, raw_desired_designs) = parsing::two_blocks(input, "the designs")?;
 
-fn check_design(design: &str, available_towels: Vec<&str>) -> bool {
-    fn helper(design: &str, towels: &Vec<&str>, memo: &mut HashMap<String, bool>) -> bool {
-        if design.is_empty() {
-            return true;
-        }
+    let available_towels: Vec<&str> = raw_available_towels.split(",").map(|towel| towel.trim()).collect();
+    let desired_designs: Vec<&str> = raw_desired_designs.lines().map(|design| design.trim()).collect();
 
-        if let Some(&result) = memo.get(design) {
-            return result;
+    for pattern in available_towels.iter().chain(&desired_designs) {
+        if pattern.is_empty() {
+            return Err(ParseError::at(input, pattern, "a stripe pattern"));
         }
-
-        for towel in towels.iter() {
-            if design.starts_with(towel) {
-                let remaining_design = &design[towel.len()..];
-                if helper(remaining_design, towels, memo) {
-                    memo.insert(design.to_string(), true);
-                    return true;
-                }
-            }
+        if let Some(index) = pattern.find(|stripe| !"wubrg".contains(stripe)) {
+            let stripe = &pattern[index..index + pattern[index..].chars().next().unwrap().len_utf8()];
+            return Err(ParseError::at(input, stripe, "one of `wubrg`"));
         }
-
-        memo.insert(design.to_string(), false);
-        false
     }
 
-    let mut memo = HashMap::new();
-    helper(design, &available_towels, &mut memo)
+    Ok((available_towels, desired_designs))
 }
 
-fn count_design_ways(design: &str, available_towels: Vec<&str>) -> usize {
 
-    fn helper(design: &str, towels: &Vec<&str>, memo: &mut HashMap<String, usize>) -> usize {
-        if design.is_empty() {
-            return 1;
-        }
+/// Whether `design` can be made out of `towels`. The cache is keyed by the design alone, the
+/// towels stay the same for the whole call tree.
+#[memoize(key = design)]
+fn check_design(design: &str, towels: &[&str]) -> bool {
+    if design.is_empty() {
+        return true;
+    }
 
-        if let Some(&cached_result) = memo.get(design) {
-            return cached_result;
+    for towel in towels.iter() {
+        if let Some(remaining_design) = design.strip_prefix(towel) {
+            if check_design(remaining_design, towels) {
+                return true;
+            }
         }
+    }
 
-        let mut ways = 0;
+    false
+}
 
-        for towel in towels.iter() {
-            if design.starts_with(towel) {
-                let remaining_design = &design[towel.len()..];
-                ways += helper(remaining_design, towels, memo);
-            }
-        }
+#[memoize(key = design)]
+fn count_design_ways(design: &str, towels: &[&str]) -> usize {
+    if design.is_empty() {
+        return 1;
+    }
 
-        memo.insert(design.to_string(), ways);
-        ways
+    let mut ways = 0;
+
+    for towel in towels.iter() {
+        if let Some(remaining_design) = design.strip_prefix(towel) {
+            ways += count_design_ways(remaining_design, towels);
+        }
     }
 
-    let mut memo = HashMap::new();
-    helper(design, &available_towels, &mut memo)
+    ways
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> usize {
-    let (available_towels, desired_designs) = parse_input(input);
-    let mut result = 0;
+pub fn part_two(available_towels: &[&str], desired_designs: &[&str]) -> usize {
+    memo::scope(|| {
+        let mut result = 0;
 
-    for design in desired_designs {
-        result += count_design_ways(design, available_towels.clone());
-    }
-
-    println!("Day 19, part 2 result: {:?}", result);
+        for design in desired_designs {
+            result += count_design_ways(design, available_towels);
+        }
 
-    result
+        result
+    })
 }
 
 #[cfg(test)]
-mod day19 {
+mod tests {
     use crate::day19::*;
     use std::assert_eq;
 
@@ -121,7 +126,8 @@ bbrgwb";
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1), 6)
+        let (available_towels, desired_designs) = parse_input(TEST_CASE_1).unwrap();
+        assert_eq!(part_one(&available_towels, &desired_designs), 6)
     }
 
 }
@@ -1,147 +1,108 @@
-use std::collections::{HashMap, HashSet};
-use itertools::Itertools;
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::graph::{self, Graph};
+use crate::utils::{parsing, BitGrid, Grid, Point};
 
-pub fn run(input: &str) {
-    part_one(input, 100, 2);
-    part_two(input, 100, 20);
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Coordinate {
-    x: usize,
-    y: usize,
-}
+pub struct Day20;
 
-impl Coordinate {
-    pub fn neighbours(&self) -> HashSet<Coordinate> {
-        let mut neighbours = HashSet::new();
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+    const NAME: &'static str = "Race Condition";
 
-        if self.y > 0 { neighbours.insert(Coordinate {x: self.x, y: self.y - 1}); };
-        if self.x > 0 { neighbours.insert(Coordinate {x: self.x - 1, y: self.y}); };
+    /// The track and the picoseconds a cheat has to save, 100 unless the input sets `@saving`.
+    type Input<'a> = (Track, usize);
 
-        neighbours.insert(Coordinate {x: self.x + 1, y: self.y});
-        neighbours.insert(Coordinate {x: self.x, y: self.y + 1});
-
-        neighbours
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (settings, track) = parsing::settings(input, &["saving"])?;
+        Ok((parse_input(track)?, settings.number("saving", 100)?))
     }
 
-    pub fn neighbours_cheated(&self, cheat_distance: usize) -> HashSet<Coordinate> {
-        let mut neighbours = HashSet::new();
-        for i in 0..=cheat_distance {
-
-            neighbours.insert(Coordinate {x: self.x + i, y: self.y.saturating_sub(cheat_distance) + i});
-            neighbours.insert(Coordinate {x: self.x + cheat_distance - i, y: self.y + i});
-            neighbours.insert(Coordinate {x: self.x.saturating_sub(i), y: self.y + cheat_distance - i});
-            neighbours.insert(Coordinate {x: self.x.saturating_sub(cheat_distance) + i, y: self.y.saturating_sub(i)});
-
-        }
+    fn part_one((track, saving): &Self::Input<'_>) -> Answer {
+        part_one(track, *saving, 2).into()
+    }
 
-        neighbours
+    fn part_two((track, saving): &Self::Input<'_>) -> Answer {
+        part_two(track, *saving, 20).into()
     }
 }
+
 #[derive(Debug, Clone)]
-struct Grid {
-    walls: HashSet<Coordinate>,
-    start: Coordinate,
-    end: Coordinate,
+pub struct Track {
+    /// The tiles that are not walls.
+    open: BitGrid,
+    start: Point,
 }
 
-impl Grid {
+impl Track {
 
-    fn new(input: &str) -> Self {
-        let grid_data: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-        let mut start: Option<Coordinate> = None;
-        let mut end: Option<Coordinate> = None;
-        let mut walls: HashSet<Coordinate> = HashSet::new();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let tiles = Grid::parse(input, "one of `#.SE`", |tile| "#.SE".contains(tile).then_some(tile))?;
+        let start = tiles.find(|&tile| tile == 'S').ok_or_else(|| ParseError::end(input, "a `S` tile"))?;
 
-        for y in 0..grid_data.len() {
-            for x in 0..grid_data[0].len() {
-                match grid_data[y][x] {
-                    'S' => { start = Some(Coordinate {x, y})},
-                    'E' => { end = Some(Coordinate {x, y})},
-                    '#' => { walls.insert(Coordinate {x, y}); },
-                    _ => {}
-
-                }
-            }
-        }
+        let mut open = BitGrid::for_grid(&tiles);
+        open.extend(tiles.find_all(|&tile| tile != '#'));
+        Ok(Self { open, start: Point::from_position(start) })
+    }
 
-        Self {
-            walls,
-            start: start.expect("Start must be found"),
-            end: end.expect("End must be found"),
-        }
+    fn is_wall(&self, point: Point) -> bool {
+        !self.open.contains_point(point)
     }
 
-    fn distance_map(self) -> HashMap<Coordinate, usize> {
-        let mut posistion: Option<Coordinate> = Some(self.start);
-        let mut distance: usize = 0;
-        let mut distance_map: HashMap<Coordinate, usize> = HashMap::new();
-
-        while let Some(pos) = posistion {
-            distance_map.insert(pos.clone(), distance);
-            posistion = pos
-                .clone()
-                .neighbours()
-                .iter()
-                .find(|coords| {
-                    !self.walls.contains(*coords) && !distance_map.contains_key(*coords)
-                })
-                .cloned();
-            distance += 1;
-        }
+    /// The distance from the start of every track tile, `None` for walls.
+    fn distance_map(&self) -> Grid<Option<usize>> {
+        let track = graph::from_fn(|point: Point| {
+            point.neighbours4().filter(|&next| !self.is_wall(next)).map(|next| (next, 1))
+        });
 
-        distance_map
+        let mut distances = Grid::filled(self.open.width(), self.open.height(), None);
+        for (point, distance) in track.bfs(self.start).reached() {
+            distances[point.position().expect("track tiles are on the map")] = Some(distance as usize);
+        }
+        distances
     }
 
-    fn count_cheats(self, distance_map: HashMap<Coordinate, usize>, cheat_time: usize, disable_collision_offset: usize ) -> usize {
-        let mut cheats: HashMap<Vec<Coordinate>, usize> = HashMap::new();
-        for entry in distance_map.iter() {
-            let cheat_start: &Coordinate = entry.0;
-            let distance = entry.1;
+    /// Cheats saving at least `cheat_time`. Every end of a cheat lies on exactly one ring around
+    /// its start, so each pair of start and end is counted once.
+    fn count_cheats(&self, distance_map: &Grid<Option<usize>>, cheat_time: usize, disable_collision_offset: usize) -> usize {
+        let mut cheats = 0;
+        for (cheat_start, distance) in distance_map.iter().filter_map(|(position, distance)| Some((position, (*distance)?))) {
+            let cheat_start = Point::from_position(cheat_start);
             for cheat_duration in 2..=disable_collision_offset {
-                for cheat_end in cheat_start.neighbours_cheated(cheat_duration) {
-                    let new_distance = distance_map.get(&cheat_end);
+                for cheat_end in cheat_start.manhattan_ring(cheat_duration as i64) {
+                    let new_distance = cheat_end.position().and_then(|position| *distance_map.get(position)?);
                     if let Some(cheated_distance) = new_distance {
-                        cheats.insert(Vec::from([cheat_start.clone(), cheat_end]), cheated_distance.saturating_sub(*distance).saturating_sub(cheat_duration));
+                        if cheated_distance.saturating_sub(distance).saturating_sub(cheat_duration) >= cheat_time {
+                            cheats += 1;
+                        }
                     }
                 }
             }
         }
 
-        cheats.iter().filter(|cheat| cheat.1 >= &cheat_time).count()
+        cheats
     }
 }
 
 #[measure_time]
-pub fn part_one(input: &str, cheat_time: usize, disable_collision_offset: usize) -> usize {
-    let grid: Grid = parse_input(input);
-    let distance_map = grid.clone().distance_map();
-    let result = grid.clone().count_cheats(distance_map, cheat_time, disable_collision_offset);
-
-    println!("Day 20, part 1 result: {:?}", result);
-
-    result
+pub fn part_one(track: &Track, cheat_time: usize, disable_collision_offset: usize) -> usize {
+    let distance_map = track.distance_map();
+    track.count_cheats(&distance_map, cheat_time, disable_collision_offset)
 }
 
-fn parse_input(input: &str) -> Grid {
-    Grid::new(input)
+fn parse_input(input: &str) -> Result<Track, ParseError> {
+    Track::new(input)
 }
 
 #[measure_time]
-pub fn part_two(input: &str, cheat_time: usize, disable_collision_offset: usize) -> usize {
-    let grid: Grid = parse_input(input);
-    let distance_map = grid.clone().distance_map();
-    let result = grid.clone().count_cheats(distance_map, cheat_time, disable_collision_offset);
-
-    println!("Day 20, part 2 result: {:?}", result);
-
-    result
+pub fn part_two(track: &Track, cheat_time: usize, disable_collision_offset: usize) -> usize {
+    let distance_map = track.distance_map();
+    track.count_cheats(&distance_map, cheat_time, disable_collision_offset)
 }
 
 #[cfg(test)]
-mod day20 {
+mod tests {
     use crate::day20::*;
     use std::assert_eq;
 
@@ -163,12 +124,12 @@ mod day20 {
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1, 30, 2), 4)
+        assert_eq!(part_one(&parse_input(TEST_CASE_1).unwrap(), 30, 2), 4)
     }
 
     #[test]
     fn test_part_two_case_one() {
-        assert_eq!(part_two(TEST_CASE_1, 75, 20), 3)
+        assert_eq!(part_two(&parse_input(TEST_CASE_1).unwrap(), 75, 20), 3)
     }
 
 }
@@ -1,11 +1,11 @@
-use measure_time_macro::measure_time;
-use std::{
-    collections::{HashMap, VecDeque},
-    usize,
-};
+use measure_time_macro::{measure_time, memoize};
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+use std::collections::{HashMap, VecDeque};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-enum Keys {
+pub enum Keys {
     Key0,
     Key1,
     Key2,
@@ -26,30 +26,31 @@ enum Keys {
 
 use Keys::*;
 
-fn parse_input(input: &str) -> Vec<(Vec<Keys>, usize)> {
+fn parse_input(input: &str) -> Result<Vec<(Vec<Keys>, usize)>, ParseError> {
     input
         .trim()
         .lines()
         .map(|line| {
-            (
-                line.chars()
-                    .map(|c| match c {
-                        '0' => Key0,
-                        '1' => Key1,
-                        '2' => Key2,
-                        '3' => Key3,
-                        '4' => Key4,
-                        '5' => Key5,
-                        '6' => Key6,
-                        '7' => Key7,
-                        '8' => Key8,
-                        '9' => Key9,
-                        'A' => KeyA,
-                        _ => unreachable!(),
+            let digits = line.strip_suffix('A').ok_or_else(|| ParseError::after(input, line, "`A`"))?;
+            Ok((
+                line.char_indices()
+                    .map(|(index, c)| match c {
+                        '0' => Ok(Key0),
+                        '1' => Ok(Key1),
+                        '2' => Ok(Key2),
+                        '3' => Ok(Key3),
+                        '4' => Ok(Key4),
+                        '5' => Ok(Key5),
+                        '6' => Ok(Key6),
+                        '7' => Ok(Key7),
+                        '8' => Ok(Key8),
+                        '9' => Ok(Key9),
+                        'A' => Ok(KeyA),
+                        _ => Err(ParseError::at(input, &line[index..index + c.len_utf8()], "a numeric keypad key")),
                     })
-                    .collect(),
-                line[0..3].parse().unwrap(),
-            )
+                    .collect::<Result<_, _>>()?,
+                parse::number(input, digits)?,
+            ))
         })
         .collect()
 }
@@ -117,24 +118,20 @@ fn find_all_shortests(
         .collect()
 }
 
+/// The number of presses on the outermost keypad to move from `path.0` to `path.1` and press it
+/// at level `current`. The tables and `max` stay the same for the whole call tree.
+#[memoize(key = &(path, current))]
 fn find_recurse(
     path: (Keys, Keys),
     max: usize,
     current: usize,
     shortest_numpads: &HashMap<Keys, HashMap<Keys, Vec<Vec<Keys>>>>,
     shortest_controls: &HashMap<Keys, HashMap<Keys, Vec<Vec<Keys>>>>,
-    memoization: &mut HashMap<(Keys, Keys), HashMap<usize, usize>>,
     last_at_level: &mut HashMap<usize, Keys>,
 ) -> usize {
     if current == max {
         1
     } else {
-        if let Some(path_cost) = memoization.get(&(path.0, path.1)) {
-            if let Some(cost) = path_cost.get(&current) {
-                return *cost;
-            }
-        }
-
         let next_path = (if current == 0 {
             shortest_numpads
         } else {
@@ -159,7 +156,6 @@ fn find_recurse(
                     current + 1,
                     shortest_numpads,
                     shortest_controls,
-                    memoization,
                     last_at_level,
                 );
                 previous = *part;
@@ -173,20 +169,33 @@ fn find_recurse(
 
         last_at_level.insert(current, next_last);
 
-        let saved_path = memoization.entry(path).or_default();
-        saved_path.insert(current, total);
-
         total
     }
 }
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+    const NAME: &'static str = "Keypad Conundrum";
+
+    type Input<'a> = Vec<(Vec<Keys>, usize)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> usize {
+pub fn part_one(codes: &[(Vec<Keys>, usize)]) -> usize {
     let numpad: HashMap<(usize, usize), Keys> = vec![
         ((0, 0), Key7),
         ((0, 1), Key8),
@@ -214,7 +223,6 @@ pub fn part_one(input: &str) -> usize {
         .into_iter()
         .collect();
 
-    let codes = parse_input(input);
     let mut complexities = 0;
     let shortests_paths_numpad = find_all_shortests(&numpad);
     let shortests_paths_control = find_all_shortests(&control);
@@ -222,7 +230,7 @@ pub fn part_one(input: &str) -> usize {
     for (code, value) in codes {
         let mut previous_key = KeyA;
         let mut len = 0;
-        for key in code {
+        for &key in code {
             len += find_recurse(
                 (previous_key, key),
                 3,
@@ -230,20 +238,17 @@ pub fn part_one(input: &str) -> usize {
                 &shortests_paths_numpad,
                 &shortests_paths_control,
                 &mut HashMap::new(),
-                &mut HashMap::new(),
             );
             previous_key = key;
         }
         complexities += value * len;
     }
 
-    println!("Day 21, part 1 result: {:?}", complexities);
-
     complexities
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> usize {
+pub fn part_two(codes: &[(Vec<Keys>, usize)]) -> usize {
     let numpad: HashMap<(usize, usize), Keys> = vec![
         ((0, 0), Key7),
         ((0, 1), Key8),
@@ -271,7 +276,6 @@ pub fn part_two(input: &str) -> usize {
         .into_iter()
         .collect();
 
-    let codes = parse_input(input);
     let mut complexities = 0;
     let shortests_paths_numpad = find_all_shortests(&numpad);
     let shortests_paths_control = find_all_shortests(&control);
@@ -279,7 +283,7 @@ pub fn part_two(input: &str) -> usize {
     for (code, value) in codes {
         let mut previous_key = KeyA;
         let mut len = 0;
-        for key in code {
+        for &key in code {
             len += find_recurse(
                 (previous_key, key),
                 26,
@@ -287,14 +291,11 @@ pub fn part_two(input: &str) -> usize {
                 &shortests_paths_numpad,
                 &shortests_paths_control,
                 &mut HashMap::new(),
-                &mut HashMap::new(),
             );
             previous_key = key;
         }
         complexities += value * len;
     }
 
-    println!("Day 21, part 2 result: {:?}", complexities);
-
     complexities
 }
@@ -1,26 +1,40 @@
 use std::collections::HashMap;
-use std::hash::{DefaultHasher, Hash, Hasher};
 use std::ops::BitXor;
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+    const NAME: &'static str = "Monkey Market";
+
+    type Input<'a> = Vec<i128>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> i128 {
-    let secrets = parse_input(input);
-
+pub fn part_one(secrets: &[i128]) -> i128 {
     let result: i128 = secrets.iter().map(|secret| calculate_nth_secret_number(*secret, 2000)).sum();
 
-    println!("Day 22, part 1 result: {:?}", result);
-
     result
 }
 
-fn parse_input(input: &str) -> Vec<i128> {
-    input.lines().map(|secret| secret.parse::<i128>().expect("All numbers are correct in input")).collect()
+fn parse_input(input: &str) -> Result<Vec<i128>, ParseError> {
+    input.lines().map(|secret| parse::number::<i128>(input, secret)).collect()
 }
 
 fn get_price(number: i128) -> i8 {
@@ -28,15 +42,11 @@ fn get_price(number: i128) -> i8 {
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> i32 {
-    let secrets = parse_input(input);
-
+pub fn part_two(secrets: &[i128]) -> i32 {
     let sequence_maps: Vec<HashMap<[i8;4], i8>> = secrets.iter().map(|secret| generate_sequences(*secret, 2000)).collect();
     let sequence_results = group_and_sum(sequence_maps);
     let result = sequence_results.values().max().cloned().unwrap();
 
-    println!("Day 22, part 2 result: {:?}", result);
-
     result
 }
 
@@ -95,24 +105,8 @@ fn calculate_secret_number(seed: i128) -> i128 {
     new_secret
 }
 
-fn calculate_price_difference(seed: i128) -> i8 {
-    let first_op = seed * 64;
-    let mut new_secret = first_op.bitxor(seed) % 16777216;
-    new_secret = ((new_secret as f64 / 32_f64).floor() as i128).bitxor(new_secret) % 16777216;
-    new_secret = (new_secret * 2048).bitxor(new_secret) % 16777216;
-
-    get_price(seed) - get_price(new_secret)
-}
-
-
-fn hash_vec(vec: &Vec<i8>) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    vec.hash(&mut hasher);
-    hasher.finish()
-}
-
 #[cfg(test)]
-mod day22 {
+mod tests {
     use crate::day22::*;
     use std::assert_eq;
 
@@ -123,7 +117,7 @@ mod day22 {
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1), 8685429 + 4700978 + 15273692 + 8667524)
+        assert_eq!(part_one(&parse_input(TEST_CASE_1).unwrap()), 8685429 + 4700978 + 15273692 + 8667524)
     }
 
     #[test]
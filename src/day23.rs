@@ -1,15 +1,35 @@
 use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::parsing;
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+    const NAME: &'static str = "LAN Party";
+
+    type Input<'a> = HashMap<&'a str, HashSet<&'a str>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> usize {
-    let computers_map: HashMap<&str, HashSet<&str>> = parse_input(input);
+pub fn part_one(computers_map: &HashMap<&str, HashSet<&str>>) -> usize {
     let mut interconnected_computers: HashSet<Vec<&&str>> = HashSet::new();
 
     computers_map
@@ -28,19 +48,14 @@ pub fn part_one(input: &str) -> usize {
                 }
             }
         });
-    println!("{:?}", interconnected_computers);
-    println!("Day 23, part 1 result: {:?}", interconnected_computers.len());
-
+    log::debug!("{:?}", interconnected_computers);
     interconnected_computers.len()
 }
 
-fn parse_input(input: &str) -> HashMap<&str, HashSet<&str>> {
+fn parse_input(input: &str) -> Result<HashMap<&str, HashSet<&str>>, ParseError> {
     let mut result: HashMap<&str, HashSet<&str>> = HashMap::new();
 
-    input.lines().map(|line| line.trim()).for_each(|line| {
-        let mut splitted = line.split("-");
-        let comp_a = splitted.next().unwrap();
-        let comp_b = splitted.next().unwrap();
+    for (comp_a, comp_b) in parsing::edges(input, input)? {
         if let Some(computer) = result.get_mut(comp_a) {
             computer.insert(comp_b);
         } else {
@@ -51,14 +66,13 @@ fn parse_input(input: &str) -> HashMap<&str, HashSet<&str>> {
         } else {
             result.insert(comp_b, HashSet::from([comp_a]));
         }
-    });
+    }
 
-    result
+    Ok(result)
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> String {
-    let computers_map: HashMap<&str, HashSet<&str>> = parse_input(input);
+pub fn part_two(computers_map: &HashMap<&str, HashSet<&str>>) -> String {
     let mut interconnected_computers: HashMap<&str, Vec<&str>> = HashMap::new();
 
     computers_map
@@ -84,7 +98,7 @@ pub fn part_two(input: &str) -> String {
                 peers_map.insert(peer, result);
             }
 
-            println!("{}, peers: {:?}, peers_map: {:?}", computer, peers, peers_map);
+            log::trace!("{}, peers: {:?}, peers_map: {:?}", computer, peers, peers_map);
 
             let sorted_peers = peers_map.iter().sorted_by(|a, b| a.1.cmp(b.1).reverse());
             'outer: for (peer, _) in sorted_peers {
@@ -95,22 +109,20 @@ pub fn part_two(input: &str) -> String {
                     if !connected_computers.contains(peer) { continue 'outer;}
                 }
 
-                let mut comps = interconnected_computers.get_mut(computer).unwrap();
-                comps.push(peer.clone());
+                let comps = interconnected_computers.get_mut(computer).unwrap();
+                comps.push(*peer);
                 comps.sort();
             }
 
         });
 
-    println!("Long values: {:?}", interconnected_computers.values().filter(|vec| vec.len() > 13));
-    let result = interconnected_computers.values().max_by(|a, b| a.len().partial_cmp(&b.len()).unwrap()).unwrap().into_iter().join(",");
-    println!("Day 23, part 2 result: {:?}", result);
-
+    log::debug!("Long values: {:?}", interconnected_computers.values().filter(|vec| vec.len() > 13));
+    let result = interconnected_computers.values().max_by(|a, b| a.len().partial_cmp(&b.len()).unwrap()).unwrap().iter().join(",");
     result
 }
 
 #[cfg(test)]
-mod day23 {
+mod tests {
     use crate::day23::*;
     use std::assert_eq;
 
@@ -149,12 +161,12 @@ td-yn";
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1), 7)
+        assert_eq!(part_one(&parse_input(TEST_CASE_1).unwrap()), 7)
     }
 
     #[test]
     fn test_part_two_case_one() {
-        assert_eq!(part_two(TEST_CASE_1), "co,de,ka,ta")
+        assert_eq!(part_two(&parse_input(TEST_CASE_1).unwrap()), "co,de,ka,ta")
     }
 
 }
@@ -2,29 +2,26 @@ use std::collections::HashMap;
 use std::ops::{BitAnd, BitOr, BitXor};
 use itertools::Itertools;
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::parsing;
 
 #[derive(Clone, PartialEq)]
-enum Operation {
-    AND,
-    OR,
-    XOR
+pub enum Operation {
+    And,
+    Or,
+    Xor
 }
 
-struct Device {
+pub struct Device {
     wires: HashMap<String, i8>,
     gates: HashMap<String, (Operation, [String; 2])>,
 }
 
 impl Device {
 
-    fn add_wire(&mut self, name: String, initial_value: i8) -> () {
-        self.wires.insert(name, initial_value);
-    }
-
-    fn add_gate(&mut self, name: String, op: Operation, inputs: [String; 2]) -> () {
-        self.gates.insert(name, (op, inputs));
-    }
-
     fn calculates_output_gates(&self) -> Vec<i8> {
         let mut gates_values: HashMap<String, i8> = HashMap::new();
         let mut output_gates: HashMap<String, Option<i8>> = self.gates
@@ -46,7 +43,7 @@ impl Device {
                         if gate.0.starts_with('z') { output_gates.insert(gate.0.clone(), Some(value));}
                     }
                     _ => {
-                        println!("One or both values are missing");
+                        log::trace!("One or both values are missing");
                     }
                 }
             }
@@ -72,9 +69,9 @@ impl Device {
 
 fn calculate_value(in1: i8, in2: i8, operation: Operation) -> i8 {
     match operation {
-        Operation::AND => { in1.bitand(in2) }
-        Operation::OR => { in1.bitor(in2) }
-        Operation::XOR => { in1.bitxor(in2) }
+        Operation::And => { in1.bitand(in2) }
+        Operation::Or => { in1.bitor(in2) }
+        Operation::Xor => { in1.bitxor(in2) }
     }
 }
 
@@ -85,66 +82,74 @@ fn change_to_binary_number(binary_number: Vec<i8>) -> isize {
     isize::from_str_radix(&reversed, 2).unwrap()
 }
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+    const NAME: &'static str = "Crossed Wires";
+
+    type Input<'a> = Device;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(input: &Self::Input<'_>) -> Answer {
+        part_one(input).into()
+    }
+
+    fn part_two(input: &Self::Input<'_>) -> Answer {
+        part_two(input).into()
+    }
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> isize {
-    let result = parse_input(input).calculates_output_gates();
-
-    println!("Day 24, part 1 result: {:?}", change_to_binary_number(result.clone()));
+pub fn part_one(device: &Device) -> isize {
+    let result = device.calculates_output_gates();
 
     change_to_binary_number(result)
 }
 
-fn parse_input(input: &str) -> Device {
+fn parse_input(input: &str) -> Result<Device, ParseError> {
+    let (raw_wires, raw_gates) = parsing::two_blocks(input, "the gates")?;
 
-    let mut parts = input.trim().split("\n\n");
-    let raw_wires = parts.next().unwrap();
-    let raw_gates = parts.next().unwrap();
-
-    let wires = raw_wires.lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            let mut parts = line.split(':');
-            let key = parts.next().unwrap().trim().to_string();
-            let value = parts.next().unwrap().trim();
-            (key, value.parse::<i8>().expect("Value needs to be binary number"))
-        })
-        .collect();
+    let wires = parsing::key_values(input, raw_wires, "`0` or `1`", |value| match value {
+        "0" => Some(0),
+        "1" => Some(1),
+        _ => None,
+    })?
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect();
 
     let gates = raw_gates.lines()
         .filter(|line| !line.trim().is_empty())
         .map(|line| {
-            let mut parts = line.split("->");
-            let first_part = parts.next().unwrap().to_string();
-            let gate_name = parts.next().unwrap().trim().to_string();
+            let (first_part, gate_name) = line.split_once("->").ok_or_else(|| ParseError::after(input, line, "`-> <wire>`"))?;
+            let first_part = first_part.trim_end();
 
-            let mut gate_input = first_part.split(" ");
+            let mut gate_input = first_part.split(' ');
             let in1 = gate_input.next().unwrap().to_string();
-            let operation = gate_input.next().unwrap();
-            let in2 = gate_input.next().unwrap().to_string();
+            let operation = gate_input.next().ok_or_else(|| ParseError::after(input, first_part, "an operation"))?;
+            let in2 = gate_input.next().ok_or_else(|| ParseError::after(input, first_part, "a second input wire"))?.to_string();
 
             let op = match operation {
-                "AND" => Operation::AND,
-                "OR" => Operation::OR,
-                "XOR" => Operation::XOR,
-                _ => panic!("Unexpected value: {}", operation),
+                "AND" => Operation::And,
+                "OR" => Operation::Or,
+                "XOR" => Operation::Xor,
+                _ => return Err(ParseError::at(input, operation, "`AND`, `OR` or `XOR`")),
             };
-            (gate_name, (op, [in1, in2]))
+            Ok((gate_name.trim().to_string(), (op, [in1, in2])))
         })
-        .collect();
+        .collect::<Result<_, ParseError>>()?;
 
-    Device {wires, gates}
+    Ok(Device {wires, gates})
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> String {
+pub fn part_two(device: &Device) -> String {
     let mut bad = vec![];
 
-    let device = parse_input(input);
     for (output, (op, ins)) in device.gates.iter() {
 
         let mut ins = ins.clone();
@@ -152,11 +157,11 @@ pub fn part_two(input: &str) -> String {
         let [in0, in1] = ins;
 
         if output.starts_with("z") && !output.ends_with("45") {
-            if *op != Operation::XOR {
+            if *op != Operation::Xor {
                 bad.push(output.clone());
             }
         } else if !(in0.starts_with("x") || in1.starts_with("y")) {
-            if *op == Operation::XOR {
+            if *op == Operation::Xor {
                 bad.push(output.clone());
             }
         } else if in0.starts_with("x") && in1.starts_with("y")
@@ -174,8 +179,8 @@ pub fn part_two(input: &str) -> String {
                 }
             }
 
-            if *op == Operation::XOR && !ops.contains(&&Operation::XOR)
-                || *op == Operation::AND && !ops.contains(&&Operation::OR)
+            if *op == Operation::Xor && !ops.contains(&&Operation::Xor)
+                || *op == Operation::And && !ops.contains(&&Operation::Or)
             {
                 bad.push(output.clone());
             }
@@ -185,13 +190,11 @@ pub fn part_two(input: &str) -> String {
     bad.sort();
     let result = bad.iter().join(",");
 
-    println!("Day 24, part 2 result: {:?}", result);
-
     result
 }
 
 #[cfg(test)]
-mod day24 {
+mod tests {
     use crate::day24::*;
     use std::assert_eq;
 
@@ -245,7 +248,7 @@ tnw OR pbm -> gnj";
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1), 2024)
+        assert_eq!(part_one(&parse_input(TEST_CASE_1).unwrap()), 2024)
     }
 
 }
@@ -1,14 +1,36 @@
 use measure_time_macro::measure_time;
+use crate::answer::Answer;
+use crate::log;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use crate::utils::parsing;
 
-pub fn run(input: &str) {
-    part_one(input);
-    part_two(input);
+type Heights = Vec<Vec<usize>>;
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const NAME: &'static str = "Code Chronicle";
+
+    type Input<'a> = (Heights, Heights);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one((keys, locks): &Self::Input<'_>) -> Answer {
+        part_one(keys, locks).into()
+    }
+
+    fn part_two(_input: &Self::Input<'_>) -> Answer {
+        part_two().into()
+    }
 }
 
 #[measure_time]
-pub fn part_one(input: &str) -> usize {
+pub fn part_one(keys: &[Vec<usize>], locks: &[Vec<usize>]) -> usize {
     let mut result = 0;
-    let (keys, locks) = parse_input(input);
 
     keys.iter()
         .for_each(|key| {
@@ -17,30 +39,41 @@ pub fn part_one(input: &str) -> usize {
             })
     });
 
-    println!("Day 25, part 1 result: {:?}", result);
-
     result
 }
 
 fn key_lock_fit(key: Vec<usize>, lock: Vec<usize>) -> bool {
-    println!("Checking key {:?} with lock {:?}", key, lock);
+    log::trace!("Checking key {:?} with lock {:?}", key, lock);
     for i in 0..key.len() {
         if key[i] + lock[i] > 5 { return false; }
     }
 
-    println!("Fitting");
+    log::trace!("Fitting");
     true
 }
 
-fn parse_input(input: &str) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
-    let mut keys: Vec<Vec<usize>> = Vec::new();
-    let mut locks: Vec<Vec<usize>> = Vec::new();
-    let mut schemas = input.trim().split("\n\n");
+fn parse_input(input: &str) -> Result<(Heights, Heights), ParseError> {
+    let mut keys: Heights = Vec::new();
+    let mut locks: Heights = Vec::new();
+    let schemas = parsing::blocks(input);
+
+    for schema in schemas {
+        let rows: Vec<&str> = schema.lines().collect();
+        if rows.len() != 7 {
+            return Err(ParseError::after(input, schema, "a schematic of 7 rows"));
+        }
+        for row in &rows {
+            if row.len() != 5 || row.chars().any(|c| c != '#' && c != '.') {
+                return Err(ParseError::at(input, row, "5 columns of `#` or `.`"));
+            }
+        }
+        if !matches!((rows[0], rows[6]), ("#####", ".....") | (".....", "#####")) {
+            return Err(ParseError::at(input, rows[0], "a lock or a key"));
+        }
 
-    schemas.for_each(|schema| {
         let mut key_locks: Vec<Vec<char>> = vec![Vec::new();5];
 
-        schema.lines()
+        rows.iter()
             .for_each(|line| line.chars()
                 .enumerate()
                 .for_each(|(j, c)| {
@@ -61,9 +94,9 @@ fn parse_input(input: &str) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
             true => {keys.push(heights)}
             false => {locks.push(heights)}
         }
-    });
+    }
 
-    (keys, locks)
+    Ok((keys, locks))
 }
 
 fn is_key(key_lock: Vec<char>) -> bool {
@@ -71,16 +104,12 @@ fn is_key(key_lock: Vec<char>) -> bool {
 }
 
 #[measure_time]
-pub fn part_two(input: &str) -> usize {
-    let result = 0;
-
-    println!("Day 25, part 2 result: {:?}", result);
-
-    result
+pub fn part_two() -> usize {
+    0
 }
 
 #[cfg(test)]
-mod day25 {
+mod tests {
     use crate::day25::*;
     use std::assert_eq;
 
@@ -126,7 +155,8 @@ mod day25 {
 
     #[test]
     fn test_part_one_case_one() {
-        assert_eq!(part_one(TEST_CASE_1), 3)
+        let (keys, locks) = parse_input(TEST_CASE_1).unwrap();
+        assert_eq!(part_one(&keys, &locks), 3)
     }
 
 }
@@ -0,0 +1,164 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use crate::verify::{self, ExpectedAnswers};
+
+/// Environment variable naming the directory that holds the `dayNN.txt` inputs.
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+/// Where the input of one day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    /// Picks the input of `day`: the explicit path if given (`-` meaning stdin), otherwise
+    /// `dayNN.txt` or, for a named input, `dayNN.<name>.txt` inside `data_dir`.
+    pub fn resolve(day: u8, explicit: Option<&Path>, data_dir: &Path, name: Option<&str>) -> InputSource {
+        match explicit {
+            Some(path) if path == Path::new("-") => InputSource::Stdin,
+            Some(path) => InputSource::File(path.to_path_buf()),
+            None => InputSource::File(data_dir.join(file_name(day, name))),
+        }
+    }
+
+    /// Reads the whole input. Only trailing line breaks are removed, other whitespace is kept.
+    pub fn read(&self) -> io::Result<String> {
+        let mut input = match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            }
+            InputSource::File(path) => fs::read_to_string(path)?,
+        };
+
+        input.truncate(input.trim_end_matches(['\n', '\r']).len());
+        Ok(input)
+    }
+
+    pub fn exists(&self) -> bool {
+        match self {
+            InputSource::Stdin => true,
+            InputSource::File(path) => path.is_file(),
+        }
+    }
+
+    /// The answers stored next to a file input; stdin has none.
+    pub fn expected(&self) -> ExpectedAnswers {
+        match self {
+            InputSource::Stdin => [None, None],
+            InputSource::File(path) => verify::load_expected(path),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+pub fn file_name(day: u8, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("day{:02}.{}.txt", day, name),
+        None => format!("day{:02}.txt", day),
+    }
+}
+
+/// The data directory: the flag, then `AOC_DATA_DIR`, then `./data`.
+pub fn resolve_data_dir(flag: Option<&Path>, env: Option<OsString>) -> PathBuf {
+    if let Some(flag) = flag {
+        return flag.to_path_buf();
+    }
+    match env.filter(|env| !env.is_empty()) {
+        Some(env) => PathBuf::from(env),
+        None => PathBuf::from("./data"),
+    }
+}
+
+/// Checks that `data_dir` exists, explaining how to point at another one if it does not.
+pub fn check_data_dir(data_dir: &Path) -> Result<(), String> {
+    if data_dir.is_dir() {
+        Ok(())
+    } else {
+        Err(format!(
+            "data directory {} does not exist, pass --data-dir <DIR> or set {}",
+            data_dir.display(),
+            DATA_DIR_VAR
+        ))
+    }
+}
+
+/// Names of the inputs of `day` found in `data_dir`, `None` being the main `dayNN.txt`.
+pub fn named_inputs(data_dir: &Path, day: u8) -> Vec<Option<String>> {
+    let prefix = format!("day{:02}", day);
+    let Ok(entries) = fs::read_dir(data_dir) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<Option<String>> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|file_name| parse_file_name(&prefix, &file_name))
+        .collect();
+    names.sort();
+    names
+}
+
+fn parse_file_name(prefix: &str, file_name: &str) -> Option<Option<String>> {
+    let rest = file_name.strip_prefix(prefix)?.strip_suffix(".txt")?;
+    match rest.strip_prefix('.') {
+        _ if rest.is_empty() => Some(None),
+        Some(name) if !name.is_empty() => Some(Some(name.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_resolve() {
+        let data_dir = Path::new("inputs");
+
+        assert_eq!(InputSource::resolve(7, None, data_dir, None), InputSource::File(PathBuf::from("inputs/day07.txt")));
+        assert_eq!(InputSource::resolve(7, None, data_dir, Some("example")), InputSource::File(PathBuf::from("inputs/day07.example.txt")));
+        assert_eq!(InputSource::resolve(7, Some(Path::new("-")), data_dir, None), InputSource::Stdin);
+        assert_eq!(InputSource::resolve(7, Some(Path::new("a.txt")), data_dir, Some("example")), InputSource::File(PathBuf::from("a.txt")));
+    }
+
+    #[test]
+    fn test_resolve_data_dir() {
+        assert_eq!(resolve_data_dir(Some(Path::new("flag")), Some(OsString::from("env"))), PathBuf::from("flag"));
+        assert_eq!(resolve_data_dir(None, Some(OsString::from("env"))), PathBuf::from("env"));
+        assert_eq!(resolve_data_dir(None, Some(OsString::new())), PathBuf::from("./data"));
+        assert_eq!(resolve_data_dir(None, None), PathBuf::from("./data"));
+    }
+
+    #[test]
+    fn test_check_data_dir() {
+        assert_eq!(check_data_dir(Path::new("src")), Ok(()));
+        assert_eq!(
+            check_data_dir(Path::new("no/such/dir")).unwrap_err(),
+            "data directory no/such/dir does not exist, pass --data-dir <DIR> or set AOC_DATA_DIR"
+        );
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(parse_file_name("day07", "day07.txt"), Some(None));
+        assert_eq!(parse_file_name("day07", "day07.example.txt"), Some(Some("example".to_string())));
+        assert_eq!(parse_file_name("day07", "day07.answers"), None);
+        assert_eq!(parse_file_name("day07", "day070.txt"), None);
+        assert_eq!(parse_file_name("day07", "day07..txt"), None);
+    }
+}
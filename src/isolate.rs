@@ -0,0 +1,92 @@
+use std::any::Any;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Finished(T),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+/// The stack of the main thread on Linux, which the days were written against. Spawned threads
+/// get 2 MiB by default, too little for the deeper recursions.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Runs `f` on a thread called `name`, catching a panic and giving up on it after `timeout`.
+/// Fails only when the thread cannot be spawned.
+///
+/// A thread that timed out cannot be stopped; it is left running in the background and its
+/// result is dropped once it eventually finishes.
+pub fn run_isolated<T, F>(name: String, timeout: Option<Duration>, f: F) -> io::Result<Outcome<T>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new().name(name).stack_size(STACK_SIZE).spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let _ = sender.send(result);
+    })?;
+
+    let result = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+
+    Ok(match result {
+        Ok(Ok(value)) => Outcome::Finished(value),
+        Ok(Err(payload)) => Outcome::Panicked(panic_message(payload.as_ref())),
+        Err(RecvTimeoutError::Timeout) => Outcome::TimedOut(timeout.unwrap_or_default()),
+        Err(RecvTimeoutError::Disconnected) => Outcome::Panicked("thread exited without a result".to_string()),
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::isolate::*;
+    use std::assert_eq;
+    use std::hint::black_box;
+
+    fn run<T: Send + 'static>(timeout: Option<Duration>, f: impl FnOnce() -> T + Send + 'static) -> Outcome<T> {
+        run_isolated("day00".to_string(), timeout, f).unwrap()
+    }
+
+    #[test]
+    fn test_run_isolated_finished() {
+        assert_eq!(run(None, || 6 * 7), Outcome::Finished(42));
+        assert_eq!(run(None, || thread::current().name().map(str::to_string)), Outcome::Finished(Some("day00".to_string())));
+    }
+
+    #[test]
+    fn test_run_isolated_stack_size() {
+        assert_eq!(run(None, || black_box([1u8; 3 * 1024 * 1024]).len()), Outcome::Finished(3 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_run_isolated_panicked() {
+        assert_eq!(run(None, || -> u8 { panic!("Pivot index out of bounds") }), Outcome::Panicked("Pivot index out of bounds".to_string()));
+        assert_eq!(run(None, || -> u8 { panic!("day {}", 3) }), Outcome::Panicked("day 3".to_string()));
+    }
+
+    #[test]
+    fn test_run_isolated_timed_out() {
+        let timeout = Duration::from_millis(10);
+        let outcome = run(Some(timeout), || thread::sleep(Duration::from_secs(5)));
+
+        assert_eq!(outcome, Outcome::TimedOut(timeout));
+    }
+}
@@ -0,0 +1,202 @@
+//! Leveled diagnostics of the days, off unless asked for with `-v` or `--log`.
+//!
+//! Every message has a level and a target, the module it is logged from without the crate name,
+//! e.g. `day17` or `utils::graph`. A [`LogConfig`] decides per target which levels are written;
+//! only those messages are formatted at all, so leaving a `trace!` in a hot loop costs a
+//! comparison. Messages go to stderr, keeping stdout for answers and reports.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    /// The level of `-v` given `count` times: debug messages for one, trace messages for more.
+    pub fn from_verbosity(count: usize) -> Level {
+        match count {
+            0 => Level::Warn,
+            1 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        })
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Level, String> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.to_string().eq_ignore_ascii_case(value))
+            .ok_or_else(|| format!("invalid log level `{}`, expected error, warn, info, debug or trace", value))
+    }
+}
+
+/// Which messages are written: the most verbose level per target, and for everything else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    pub default: Level,
+    pub targets: Vec<(String, Level)>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig { default: Level::Warn, targets: Vec::new() }
+    }
+}
+
+impl LogConfig {
+    /// Adds the directives of a `--log` value such as `day17=trace,day23=debug` or `info`; a
+    /// bare level applies to every target. Later directives win.
+    pub fn add_directives(&mut self, spec: &str) -> Result<(), String> {
+        for directive in spec.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) if !target.trim().is_empty() => {
+                    let target = target.trim().to_string();
+                    let level = level.trim().parse()?;
+                    self.targets.retain(|(known, _)| *known != target);
+                    self.targets.push((target, level));
+                }
+                Some(_) => return Err(format!("invalid log directive `{}`, expected <target>=<level>", directive)),
+                None => self.default = directive.parse()?,
+            }
+        }
+        Ok(())
+    }
+
+    /// The most verbose level written for `target`; the longest matching target counts.
+    pub fn level(&self, target: &str) -> Level {
+        self.targets
+            .iter()
+            .filter(|(known, _)| target == known || target.strip_prefix(known.as_str()).is_some_and(|rest| rest.starts_with("::")))
+            .max_by_key(|(known, _)| known.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max_level(&self) -> Level {
+        self.targets.iter().map(|&(_, level)| level).fold(self.default, Level::max)
+    }
+}
+
+static LOG_CONFIG: OnceLock<LogConfig> = OnceLock::new();
+/// The most verbose level any target writes, checked before anything else.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+/// Sets which messages are written for the rest of the process; only the first call counts.
+pub fn init(config: LogConfig) {
+    let max_level = config.max_level();
+    if LOG_CONFIG.set(config).is_ok() {
+        MAX_LEVEL.store(max_level as u8, Ordering::Relaxed);
+    }
+}
+
+/// `module_path` without the crate name.
+fn target(module_path: &str) -> &str {
+    module_path.split_once("::").map_or(module_path, |(_, target)| target)
+}
+
+/// Whether a message at `level` from `module_path` is written; used by the macros.
+pub fn enabled(level: Level, module_path: &str) -> bool {
+    if level as u8 > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    match LOG_CONFIG.get() {
+        Some(config) => level <= config.level(target(module_path)),
+        None => level <= Level::Warn,
+    }
+}
+
+/// Writes one message to stderr; used by the macros after [`enabled`].
+pub fn write(level: Level, module_path: &str, message: fmt::Arguments) {
+    let _ = writeln!(io::stderr().lock(), "[{} {}] {}", level, target(module_path), message);
+}
+
+/// Logs a message at a [`Level`], formatting it only when it is written.
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level, module_path!()) {
+            $crate::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+/// Logs a problem that stopped something the user asked for, e.g. writing frames.
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+/// Logs what a day found along the way, e.g. intermediate results.
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+/// Logs every step of a day, e.g. each instruction of a program.
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+pub(crate) use {debug, error, log, trace};
+
+#[cfg(test)]
+mod tests {
+    use crate::log::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_add_directives() {
+        let mut config = LogConfig::default();
+        config.add_directives("day17=trace, info,day23=Debug").unwrap();
+        config.add_directives("day17=debug").unwrap();
+
+        assert_eq!(config.default, Level::Info);
+        assert_eq!(config.targets, vec![("day23".to_string(), Level::Debug), ("day17".to_string(), Level::Debug)]);
+        assert_eq!(config.max_level(), Level::Debug);
+
+        assert!(config.add_directives("day17=loud").is_err());
+        assert!(config.add_directives("=trace").is_err());
+    }
+
+    #[test]
+    fn test_level_per_target() {
+        let mut config = LogConfig::default();
+        config.add_directives("utils=debug,utils::graph=trace,day1=info").unwrap();
+
+        assert_eq!(config.level(target("aoc2024::utils::graph")), Level::Trace);
+        assert_eq!(config.level(target("aoc2024::utils::grid")), Level::Debug);
+        assert_eq!(config.level(target("aoc2024::day1")), Level::Info);
+        assert_eq!(config.level(target("aoc2024::day17")), Level::Warn);
+    }
+
+    #[test]
+    fn test_from_verbosity() {
+        assert_eq!(Level::from_verbosity(0), Level::Warn);
+        assert_eq!(Level::from_verbosity(1), Level::Debug);
+        assert_eq!(Level::from_verbosity(2), Level::Trace);
+        assert_eq!(Level::from_verbosity(3), Level::Trace);
+        assert_eq!("TRACE".parse::<Level>(), Ok(Level::Trace));
+    }
+}
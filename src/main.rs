@@ -1,70 +1,518 @@
+mod cli;
+
 use std::env;
 use std::fs;
-use std::time::Instant;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use aoc2024::alloc::{self, AllocStats, CountingAllocator};
+use aoc2024::bench::baseline::{self, Baseline, Comparison};
+use aoc2024::bench::{self, Stats};
+use aoc2024::input::{self, InputSource};
+use aoc2024::isolate::{self, Outcome};
+use aoc2024::parse::ParseError;
+use aoc2024::pool::{self, Slot};
+use aoc2024::report::{self, Record};
+use aoc2024::solution::{DayRun, Part, Puzzle};
+use aoc2024::verify::{self, ExpectedAnswers, Status};
+use cli::{Command, Options, Timings};
+use measure_time_runtime::memo::{self, MemoStats};
+use measure_time_runtime::Timing;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn elapsed_since(start_time: &Instant) -> String {
-    let elapsed = start_time.elapsed().as_micros();
-    if elapsed >= 1_000_000 {
-        let elapsed = elapsed as f64 / 1_000_000.0;
-        format!("{elapsed:.1}s")
-    } else if elapsed >= 1000 {
-        let elapsed = elapsed as f64 / 1000.0;
-        format!("{elapsed:.1}ms")
-    } else {
-        format!("{elapsed}µs")
-    }
-}
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let days: Vec<_> = match args.len() {
-        1 => (25..=25).collect(),
-        _ => args.iter().skip(1).map(|d| d.parse().unwrap()).collect(),
+    bench::format_duration(start_time.elapsed())
+}
+
+/// Looks up the solution and reads the input of `day`, reporting what is missing on stderr.
+fn load_day(options: &Options, day: u8) -> Option<(&'static dyn Puzzle, InputSource, String)> {
+    let Some(solution) = aoc2024::find_solution(day) else {
+        eprintln!("Day {}: no solution registered", day);
+        return None;
+    };
+    let source = options.input_source(day);
+    match source.read() {
+        Ok(input) => Some((solution, source, input)),
+        Err(error) => {
+            eprintln!("Day {}: cannot read input {}: {}", day, source, error);
+            None
+        }
+    }
+}
+
+/// Prints the timings of one day, folding repeated calls of the same function into one line.
+fn report_timings(output: Timings, timings: &[Timing]) {
+    let mut totals: Vec<(&str, usize, Duration)> = Vec::new();
+    for timing in timings {
+        match totals.iter_mut().find(|(label, _, _)| *label == timing.label) {
+            Some((_, calls, total)) => {
+                *calls += 1;
+                *total += timing.duration;
+            }
+            None => totals.push((timing.label, 1, timing.duration)),
+        }
+    }
+
+    for (label, calls, total) in totals {
+        let line = match calls {
+            1 => format!("Function '{}' executed in: {:?}", label, total),
+            _ => format!("Function '{}' executed {} times in: {:?}", label, calls, total),
+        };
+        print_timing_line(output, &line);
+    }
+}
+
+/// Prints the cache statistics of the `#[memoize]` functions of one day, next to its timings.
+fn report_memo_stats(output: Timings, stats: &[MemoStats]) {
+    for stats in stats {
+        let line = format!(
+            "Function '{}' memoized: {} hits, {} misses ({:.1}% hit rate)",
+            stats.label,
+            stats.hits,
+            stats.misses,
+            stats.hit_rate() * 100.0
+        );
+        print_timing_line(output, &line);
+    }
+}
+
+/// Prints what parsing, each part and the whole day allocated, for `--profile-alloc`.
+fn report_allocations(solved: &Solved) {
+    let Outcome::Finished(Ok(run)) = &solved.outcome else {
+        return;
+    };
+    let print = |label: String, stats: &AllocStats| println!("Memory {:<7} {}", format!("{}:", label), stats);
+
+    print("parse".to_string(), &run.parse_alloc);
+    for part_run in &run.parts {
+        print(format!("part {}", part_run.part), &part_run.alloc);
+    }
+    print("total".to_string(), &solved.alloc);
+}
+
+fn print_timing_line(output: Timings, line: &str) {
+    match output {
+        Timings::Stdout => println!("{}", line),
+        Timings::Stderr => eprintln!("{}", line),
+        Timings::Off => {}
+    }
+}
+
+/// Everything one day produced, computed on a worker and printed in day order.
+struct Solved {
+    solution: &'static dyn Puzzle,
+    source: InputSource,
+    outcome: Outcome<Result<DayRun, ParseError>>,
+    timings: Vec<Timing>,
+    memo_stats: Vec<MemoStats>,
+    /// What the whole day allocated, parsing included.
+    alloc: AllocStats,
+    elapsed: Duration,
+    expected: ExpectedAnswers,
+}
+
+impl Solved {
+    fn records(&self, parts: &[Part]) -> Vec<Record> {
+        let day = self.solution.day();
+        let status = match &self.outcome {
+            Outcome::Finished(Ok(run)) => {
+                return run.parts.iter().map(|part_run| Record {
+                    day,
+                    part: part_run.part,
+                    answer: Some(part_run.answer.clone()),
+                    parse_time: run.parse_time,
+                    solve_time: part_run.solve_time,
+                    status: verify::check(&part_run.answer, self.expected[part_run.part.index()].as_ref()),
+                }).collect();
+            }
+            Outcome::Finished(Err(_)) => Status::Invalid,
+            Outcome::Panicked(_) => Status::Panicked,
+            Outcome::TimedOut(_) => Status::TimedOut,
+        };
+
+        parts.iter().map(|&part| Record {
+            day,
+            part,
+            answer: None,
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            status,
+        }).collect()
+    }
+
+    /// Why the day produced no answers, if it did not finish.
+    fn failure(&self) -> Option<String> {
+        match &self.outcome {
+            Outcome::Finished(Ok(_)) => None,
+            Outcome::Finished(Err(_)) => Some("has invalid input".to_string()),
+            Outcome::Panicked(message) => Some(format!("panicked: {}", message)),
+            Outcome::TimedOut(timeout) => Some(format!("timed out after {}", bench::format_duration(*timeout))),
+        }
+    }
+}
+
+/// Solves `day` on its own thread, so a panic or a timeout only costs this day. Reports on stderr
+/// when the input is missing or the thread cannot be started.
+fn solve_day(options: &Options, day: u8) -> Option<Solved> {
+    let (solution, source, input) = load_day(options, day)?;
+    let parts = options.parts.clone();
+    let start_time = Instant::now();
+    let outcome = isolate::run_isolated(format!("day{:02}", day), options.timeout, move || {
+        alloc::measure(|| memo::capture_stats(|| measure_time_runtime::capture(|| solution.run(&input, &parts))))
+    });
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            eprintln!("Day {}: cannot start a thread: {}", day, error);
+            return None;
+        }
+    };
+    let elapsed = start_time.elapsed();
+
+    let (outcome, timings, memo_stats, alloc) = match outcome {
+        Outcome::Finished((((run, timings), memo_stats), alloc)) => (Outcome::Finished(run), timings, memo_stats, alloc),
+        Outcome::Panicked(message) => (Outcome::Panicked(message), Vec::new(), Vec::new(), AllocStats::default()),
+        Outcome::TimedOut(timeout) => (Outcome::TimedOut(timeout), Vec::new(), Vec::new(), AllocStats::default()),
     };
+
+    Some(Solved {
+        solution,
+        expected: source.expected(),
+        source,
+        outcome,
+        timings,
+        memo_stats,
+        alloc,
+        elapsed,
+    })
+}
+
+fn print_critical_path(days: &[u8], slots: &[Slot]) {
+    let path = pool::critical_path(slots);
+    let steps: Vec<String> = path.iter()
+        .map(|&index| format!("day {} ({})", days[index], bench::format_duration(slots[index].duration())))
+        .collect();
+    let busy: Duration = slots.iter().map(Slot::duration).sum();
+
+    println!("CRITICAL PATH: {}", steps.join(" -> "));
+    println!("BUSY TIME: {} across all workers", bench::format_duration(busy));
+}
+
+fn run_days(options: &Options) -> ExitCode {
+    let text = !options.report_to_stdout();
+    let mut records = Vec::new();
+    let (mut finished, mut invalid, mut panicked, mut timed_out, mut missing_days) = (0, 0, 0, 0, 0);
     let global_start_time = Instant::now();
-    for day in &days {
-        println!("Day {}:", day);
-        let path = format!("./data/day{:02}.txt", day);
-        let input = fs::read_to_string(&path);
-        let start_time = Instant::now();
-        if let Ok(input) = input {
-            let input = input.trim_end();
-            let day_func = match day {
-                1 => aoc2024::day01::run,
-                2 => aoc2024::day02::run,
-                3 => aoc2024::day03::run,
-                4 => aoc2024::day04::run,
-                5 => aoc2024::day05::run,
-                6 => aoc2024::day06::run,
-                7 => aoc2024::day07::run,
-                8 => aoc2024::day08::run,
-                9 => aoc2024::day09::run,
-                10 => aoc2024::day10::run,
-                11 => aoc2024::day11::run,
-                12 => aoc2024::day12::run,
-                13 => aoc2024::day13::run,
-                14 => aoc2024::day14::run,
-                15 => aoc2024::day15::run,
-                16 => aoc2024::day16::run,
-                17 => aoc2024::day17::run,
-                18 => aoc2024::day18::run,
-                19 => aoc2024::day19::run,
-                20 => aoc2024::day20::run,
-                21 => aoc2024::day21::run,
-                22 => aoc2024::day22::run,
-                23 => aoc2024::day23::run,
-                24 => aoc2024::day24::run,
-                25 => aoc2024::day25::run,
-                _ => unreachable!(),
-            };
-            day_func(input);
-            println!("Time: {}", elapsed_since(&start_time));
-        } else {
-            println!("ERROR: no data");
+    let slots = pool::run_ordered(&options.days, options.jobs, |&day| solve_day(options, day), |&day, solved| {
+        let Some(solved) = solved else {
+            missing_days += 1;
+            return;
+        };
+        match solved.outcome {
+            Outcome::Finished(Ok(_)) => finished += 1,
+            Outcome::Finished(Err(_)) => invalid += 1,
+            Outcome::Panicked(_) => panicked += 1,
+            Outcome::TimedOut(_) => timed_out += 1,
+        }
+        if text {
+            println!("Day {}: {}", day, solved.solution.name());
+            println!("Input: {}", solved.source);
+        }
+        report_timings(options.timings, &solved.timings);
+        report_memo_stats(options.timings, &solved.memo_stats);
+        if let (true, Some(failure)) = (text, solved.failure()) {
+            println!("Day {} {}", day, failure);
+        }
+        if let (true, Outcome::Finished(Err(error))) = (text, &solved.outcome) {
+            println!("error: {}", error);
+            println!("{}", error.excerpt());
+        }
+        for record in solved.records(&options.parts) {
+            if let (true, Some(answer)) = (text, &record.answer) {
+                println!("Day {}, part {} result: {}", day, record.part, answer);
+            }
+            records.push(record);
+        }
+        if text && options.profile_alloc {
+            report_allocations(&solved);
         }
+        for recording in aoc2024::render::take_recordings(day) {
+            if text {
+                println!("Frames: {} written to {}", recording.frames, recording.path.display());
+            }
+        }
+        if text {
+            println!("Time: {}", bench::format_duration(solved.elapsed));
+            println!();
+        }
+    });
+    if text && options.days.len() > 1 {
+        println!("TOTAL TIME: {}", elapsed_since(&global_start_time));
+        if options.jobs > 1 {
+            print_critical_path(&options.days, &slots);
+        }
+    }
+    if text && (options.days.len() > 1 || invalid + panicked + timed_out > 0) {
+        println!(
+            "{} finished, {} invalid, {} panicked, {} timed out, {} without input",
+            finished, invalid, panicked, timed_out, missing_days
+        );
+    }
+
+    let failed = invalid + panicked + timed_out + missing_days > 0;
+    if !write_report(options, &records) || failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+/// Writes the requested JSON or CSV report, if any; returns false when it could not be written.
+fn write_report(options: &Options, records: &[Record]) -> bool {
+    let Some(format) = options.report else {
+        return true;
+    };
+    let report = report::render(format, records);
+
+    match &options.report_output {
+        None => {
+            print!("{}", report);
+            true
+        }
+        Some(path) => match fs::write(path, report) {
+            Ok(()) => true,
+            Err(error) => {
+                eprintln!("error: cannot write report {}: {}", path.display(), error);
+                false
+            }
+        },
+    }
+}
+
+fn print_stats(label: &str, stats: &Stats) {
+    println!(
+        "  {:<8}  {:>7}  {:>10}  {:>10}  {:>10}  {:>10}",
+        label,
+        stats.samples,
+        bench::format_duration(stats.min),
+        bench::format_duration(stats.median),
+        bench::format_duration(stats.mean),
+        bench::format_duration(stats.stddev)
+    );
+}
+
+/// Prints how every measurement moved since the baseline; returns the number of regressions.
+fn print_comparison(name: &str, comparisons: &[Comparison], threshold: f64) -> usize {
+    println!("Compared with baseline '{}':", name);
+    println!("  {:>3}  {:<8}  {:>10}  {:>10}  {:>8}", "DAY", "", "BASELINE", "CURRENT", "CHANGE");
+    for comparison in comparisons {
+        let verdict = match comparison {
+            Comparison { regression: true, .. } => "REGRESSION",
+            Comparison { significant: false, .. } => "noise",
+            Comparison { change, .. } if *change < 0.0 => "faster",
+            _ => "slower",
+        };
+        println!(
+            "  {:>3}  {:<8}  {:>10}  {:>10}  {:>+7.1}%  {}",
+            comparison.day,
+            comparison.measurement.to_string(),
+            bench::format_duration(comparison.before.mean),
+            bench::format_duration(comparison.after.mean),
+            comparison.change * 100.0,
+            verdict
+        );
+    }
+
+    let regressions = comparisons.iter().filter(|comparison| comparison.regression).count();
+    println!("{} significant regressions beyond {:.1}%", regressions, threshold * 100.0);
+    regressions
+}
+
+fn bench_days(options: &Options) -> ExitCode {
+    let (mut missing_days, mut invalid_days) = (0, 0);
+    let mut total_median = Duration::ZERO;
+    let mut current = Baseline::default();
+    for &day in &options.days {
+        let Some((solution, _, input)) = load_day(options, day) else {
+            missing_days += 1;
+            continue;
+        };
+        let result = match solution.bench(&input, &options.parts, &options.bench) {
+            Ok(result) => result,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                eprintln!("{}", error.excerpt());
+                invalid_days += 1;
+                continue;
+            }
+        };
+
+        println!("Day {}: {}", day, solution.name());
+        println!("  {:<8}  {:>7}  {:>10}  {:>10}  {:>10}  {:>10}", "", "SAMPLES", "MIN", "MEDIAN", "MEAN", "STDDEV");
+        print_stats("parse", &result.parse);
+        for (part, stats) in &result.parts {
+            print_stats(&format!("part {}", part), stats);
+        }
+        print_stats("total", &result.total);
         println!();
+        total_median += result.total.median;
+        current.add_day(day, &result);
     }
-    if days.len() > 1 {
-        println!("TOTAL TIME: {}", elapsed_since(&global_start_time));
+    if options.days.len() > 1 {
+        println!("TOTAL MEDIAN TIME: {}", bench::format_duration(total_median));
+    }
+
+    let mut failed = missing_days + invalid_days > 0;
+    if let Some(name) = &options.baseline {
+        match Baseline::load(&baseline::path(&options.data_dir, name)) {
+            Ok(saved) => {
+                println!();
+                let comparisons = baseline::compare(&saved, &current, options.threshold);
+                failed |= print_comparison(name, &comparisons, options.threshold) > 0;
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                failed = true;
+            }
+        }
+    }
+    if let Some(name) = &options.save_baseline {
+        let path = baseline::path(&options.data_dir, name);
+        match current.save(&path) {
+            Ok(()) => println!("Saved baseline '{}' to {}", name, path.display()),
+            Err(error) => {
+                eprintln!("error: cannot write baseline {}: {}", path.display(), error);
+                failed = true;
+            }
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn verify_days(options: &Options) -> ExitCode {
+    let text = !options.report_to_stdout();
+    let mut records = Vec::new();
+    let (mut passed, mut failed, mut missing, mut invalid, mut panicked, mut timed_out) = (0, 0, 0, 0, 0, 0);
+
+    if text {
+        println!("{:>3}  {:>4}  {:<8}  {:<20}  EXPECTED", "DAY", "PART", "STATUS", "ANSWER");
+    }
+    pool::run_ordered(&options.days, options.jobs, |&day| solve_day(options, day), |&day, solved| {
+        let Some(solved) = solved else {
+            if text {
+                println!("{:>3}  {:>4}  {:<8}  no input", day, "-", Status::Missing);
+            }
+            missing += options.parts.len();
+            return;
+        };
+
+        for record in solved.records(&options.parts) {
+            match record.status {
+                Status::Pass => passed += 1,
+                Status::Fail => failed += 1,
+                Status::Missing => missing += 1,
+                Status::Invalid => invalid += 1,
+                Status::Panicked => panicked += 1,
+                Status::TimedOut => timed_out += 1,
+            }
+            if text {
+                let answer = match &record.answer {
+                    Some(answer) => answer.to_string(),
+                    None => solved.failure().unwrap_or_default(),
+                };
+                println!(
+                    "{:>3}  {:>4}  {:<8}  {:<20}  {}",
+                    day,
+                    record.part,
+                    record.status,
+                    answer,
+                    solved.expected[record.part.index()].as_ref().map(|expected| expected.to_string()).unwrap_or_default()
+                );
+            }
+            records.push(record);
+        }
+        if let (true, Outcome::Finished(Err(error))) = (text, &solved.outcome) {
+            eprintln!("error: {}", error);
+            eprintln!("{}", error.excerpt());
+        }
+    });
+
+    if text {
+        println!();
+        println!(
+            "{} passed, {} failed, {} missing, {} invalid, {} panicked, {} timed out",
+            passed, failed, missing, invalid, panicked, timed_out
+        );
+    }
+
+    let failed = failed + invalid + panicked + timed_out > 0;
+    if !write_report(options, &records) || failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn list_days(options: &Options) -> ExitCode {
+    for &day in &options.days {
+        let Some(solution) = aoc2024::find_solution(day) else {
+            continue;
+        };
+        let inputs: Vec<String> = input::named_inputs(&options.data_dir, day).into_iter().map(|name| {
+            let path = options.data_dir.join(input::file_name(day, name.as_deref()));
+            let name = name.unwrap_or_else(|| "main".to_string());
+            if verify::answers_path(&path).exists() { format!("{} (answers)", name) } else { name }
+        }).collect();
+        let inputs = if inputs.is_empty() { "no input".to_string() } else { inputs.join(", ") };
+        println!("{:>3}  {:<24}  {}", day, solution.name(), inputs);
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            eprintln!();
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(&day) = options.days.first().filter(|_| options.input.is_some()) {
+        let source = options.input_source(day);
+        if !source.exists() {
+            eprintln!("error: input file {} does not exist", source);
+            return ExitCode::from(2);
+        }
+    }
+    let reads_data_dir = matches!(options.command, Command::Run | Command::Bench | Command::Verify);
+    if let (true, None, Err(error)) = (reads_data_dir, &options.input, input::check_data_dir(&options.data_dir)) {
+        eprintln!("error: {}", error);
+        return ExitCode::from(2);
+    }
+
+    match options.timings {
+        Timings::Stdout => measure_time_runtime::set_collector(measure_time_runtime::Stdout),
+        Timings::Stderr => measure_time_runtime::set_collector(measure_time_runtime::Stderr),
+        Timings::Off => measure_time_runtime::silence(),
+    }
+
+    if let Some(frames) = &options.frames {
+        aoc2024::render::record_frames(frames.clone());
+    }
+    if let Some(visualize) = options.visualize {
+        aoc2024::render::terminal::visualize(visualize);
+    }
+    if options.profile_alloc {
+        alloc::enable();
+    }
+    aoc2024::log::init(options.log.clone());
+
+    match options.command {
+        Command::Run => run_days(&options),
+        Command::Bench => bench_days(&options),
+        Command::Verify => verify_days(&options),
+        Command::List => list_days(&options),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
     }
 }
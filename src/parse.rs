@@ -0,0 +1,131 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Input that a day could not parse, pointing at the offending spot.
+///
+/// Lines and columns count from 1, columns in characters. The text of the offending line is kept
+/// so the runner can print an excerpt without holding on to the whole input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// An error at `token`, which must be a slice of `input`.
+    pub fn at(input: &str, token: &str, expected: impl Into<String>) -> ParseError {
+        let offset = offset_in(input, token);
+        ParseError::at_offset(input, offset, token.len(), expected)
+    }
+
+    /// An error right after `token`, for something missing at the end of it.
+    pub fn after(input: &str, token: &str, expected: impl Into<String>) -> ParseError {
+        let offset = offset_in(input, token) + token.len();
+        ParseError::at_offset(input, offset, 0, expected)
+    }
+
+    /// An error at the end of `input`, for input that stops too early.
+    pub fn end(input: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::at_offset(input, input.len(), 0, expected)
+    }
+
+    fn at_offset(input: &str, offset: usize, length: usize, expected: impl Into<String>) -> ParseError {
+        let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[line_start..].find('\n').map_or(input.len(), |index| line_start + index);
+        let source_line = input[line_start..line_end].trim_end_matches('\r');
+        let found = &input[offset..(offset + length).min(line_end)];
+
+        ParseError {
+            day: 0,
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            expected: expected.into(),
+            found: found.to_string(),
+            source_line: source_line.to_string(),
+        }
+    }
+
+    pub fn in_day(self, day: u8) -> ParseError {
+        ParseError { day, ..self }
+    }
+
+    /// The offending line with carets under the part that did not parse.
+    pub fn excerpt(&self) -> String {
+        let number = self.line.to_string();
+        let padding = " ".repeat(number.len());
+        let carets = "^".repeat(self.found.chars().count().max(1));
+
+        format!(
+            "{} |\n{} | {}\n{} | {}{}",
+            padding,
+            number,
+            self.source_line,
+            padding,
+            " ".repeat(self.column - 1),
+            carets
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {}, line {}, column {}: expected {}, found ", self.day, self.line, self.column, self.expected)?;
+        if self.found.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "`{}`", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `token`, a slice of `input`, as a number.
+pub fn number<T: FromStr>(input: &str, token: &str) -> Result<T, ParseError> {
+    token.parse().map_err(|_| ParseError::at(input, token, "a number"))
+}
+
+/// Byte offset of `token` inside `input`. A token that is not a slice of `input` is placed at
+/// its end, which only happens through a bug in the caller.
+fn offset_in(input: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+    debug_assert!(offset + token.len() <= input.len(), "token is not a slice of the input");
+    if offset + token.len() <= input.len() { offset } else { input.len() }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::*;
+    use std::assert_eq;
+
+    const INPUT: &str = "47|53\n97|x3\n\n75,47";
+
+    #[test]
+    fn test_number() {
+        let line = INPUT.lines().nth(1).unwrap();
+
+        assert_eq!(number::<i32>(INPUT, &line[..2]), Ok(97));
+
+        let error = number::<i32>(INPUT, &line[3..]).unwrap_err().in_day(5);
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.to_string(), "day 5, line 2, column 4: expected a number, found `x3`");
+        assert_eq!(error.excerpt(), "  |\n2 | 97|x3\n  |    ^^");
+    }
+
+    #[test]
+    fn test_after_and_end() {
+        let line = INPUT.lines().next().unwrap();
+
+        let error = ParseError::after(INPUT, line, "`,`");
+        assert_eq!((error.line, error.column, error.found.as_str()), (1, 6, ""));
+        assert_eq!(error.excerpt(), "  |\n1 | 47|53\n  |      ^");
+
+        let error = ParseError::end(INPUT, "a blank line");
+        assert_eq!((error.line, error.column), (4, 6));
+        assert!(error.to_string().ends_with("expected a blank line, found end of line"));
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// When and where one job ran, as offsets from the start of the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub worker: usize,
+    pub started: Duration,
+    pub finished: Duration,
+}
+
+impl Slot {
+    pub fn duration(&self) -> Duration {
+        self.finished - self.started
+    }
+}
+
+/// Runs `work` for every job on `workers` threads, taking the jobs in order, and hands the results
+/// to `emit` on the calling thread in job order, each one as soon as it and all before it are done.
+///
+/// Returns the slot of every job, in job order.
+pub fn run_ordered<J, R>(
+    jobs: &[J],
+    workers: usize,
+    work: impl Fn(&J) -> R + Sync,
+    mut emit: impl FnMut(&J, R),
+) -> Vec<Slot>
+where
+    J: Sync,
+    R: Send,
+{
+    let next_job = AtomicUsize::new(0);
+    let pool_start = Instant::now();
+    let (sender, receiver) = mpsc::channel();
+    let mut slots = vec![None; jobs.len()];
+
+    thread::scope(|scope| {
+        for worker in 0..workers.clamp(1, jobs.len().max(1)) {
+            let sender = sender.clone();
+            let (next_job, work) = (&next_job, &work);
+            scope.spawn(move || loop {
+                let index = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(index) else {
+                    break;
+                };
+                let started = pool_start.elapsed();
+                let result = work(job);
+                let slot = Slot { worker, started, finished: pool_start.elapsed() };
+                if sender.send((index, slot, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut next_emit = 0;
+        for (index, slot, result) in receiver {
+            slots[index] = Some(slot);
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next_emit) {
+                emit(&jobs[next_emit], result);
+                next_emit += 1;
+            }
+        }
+    });
+
+    slots.into_iter().map(|slot| slot.expect("every job ran")).collect()
+}
+
+/// The jobs, in order, of the worker that finished last: the chain that bounds the wall time.
+pub fn critical_path(slots: &[Slot]) -> Vec<usize> {
+    let Some(last) = slots.iter().max_by_key(|slot| slot.finished) else {
+        return Vec::new();
+    };
+
+    let mut path: Vec<usize> = (0..slots.len()).filter(|&index| slots[index].worker == last.worker).collect();
+    path.sort_by_key(|&index| slots[index].started);
+    path
+}
+
+/// Number of workers used for `--jobs auto`.
+pub fn available_workers() -> usize {
+    thread::available_parallelism().map(|workers| workers.get()).unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_run_ordered_emits_in_job_order() {
+        let jobs: Vec<u64> = vec![30, 1, 20, 2, 10, 3];
+        let mut emitted = Vec::new();
+
+        let slots = run_ordered(&jobs, 3, |&millis| {
+            thread::sleep(Duration::from_millis(millis));
+            millis * 2
+        }, |&job, result| emitted.push((job, result)));
+
+        assert_eq!(emitted, vec![(30, 60), (1, 2), (20, 40), (2, 4), (10, 20), (3, 6)]);
+        assert_eq!(slots.len(), jobs.len());
+        assert!(slots.iter().all(|slot| slot.worker < 3 && slot.started <= slot.finished));
+    }
+
+    #[test]
+    fn test_run_ordered_without_jobs() {
+        let slots = run_ordered(&[] as &[u8], 4, |_| (), |_, _| panic!("nothing to emit"));
+
+        assert!(slots.is_empty());
+    }
+
+    #[test]
+    fn test_critical_path() {
+        let slot = |worker, started, finished| Slot {
+            worker,
+            started: Duration::from_millis(started),
+            finished: Duration::from_millis(finished),
+        };
+        let slots = [slot(0, 0, 50), slot(1, 0, 10), slot(1, 10, 40), slot(0, 50, 60), slot(1, 40, 90)];
+
+        assert_eq!(critical_path(&slots), vec![1, 2, 4]);
+        assert_eq!(critical_path(&[]), Vec::<usize>::new());
+    }
+}
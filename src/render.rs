@@ -0,0 +1,363 @@
+pub mod encode;
+pub mod terminal;
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use crate::log;
+use crate::utils::{Grid, Point};
+use encode::GifWriter;
+
+pub type Rgb = [u8; 3];
+
+/// An image is a grid of colours, so everything a [`Grid`] offers works on it.
+pub type Image = Grid<Rgb>;
+
+/// How long one frame of an animated GIF is shown, in hundredths of a second.
+pub const GIF_FRAME_DELAY: u16 = 5;
+
+/// Colours for the values of a grid, with a fallback for every value not listed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette<T> {
+    colours: Vec<(T, Rgb)>,
+    fallback: Rgb,
+}
+
+impl<T: PartialEq> Palette<T> {
+    pub fn new(fallback: Rgb) -> Palette<T> {
+        Palette { colours: Vec::new(), fallback }
+    }
+
+    pub fn with(mut self, value: T, colour: Rgb) -> Palette<T> {
+        self.colours.push((value, colour));
+        self
+    }
+
+    pub fn colour(&self, value: &T) -> Rgb {
+        self.colours.iter().find(|(known, _)| known == value).map_or(self.fallback, |&(_, colour)| colour)
+    }
+}
+
+/// One pixel per cell of `grid`, coloured by `palette`.
+pub fn render<T: PartialEq>(grid: &Grid<T>, palette: &Palette<T>) -> Image {
+    grid.map(|value| palette.colour(value))
+}
+
+/// A `width` x `height` image of a point set, points in `palette`'s colour for `true` and the
+/// rest in its colour for `false`. Points outside the image are left out.
+pub fn render_points(points: impl IntoIterator<Item = Point>, width: usize, height: usize, palette: &Palette<bool>) -> Image {
+    let mut image = Image::filled(width, height, palette.colour(&false));
+    for point in points {
+        if let Some(pixel) = point.position().and_then(|position| image.get_mut(position)) {
+            *pixel = palette.colour(&true);
+        }
+    }
+    image
+}
+
+/// Blows every pixel up into a `factor` x `factor` square.
+pub fn scale(image: &Image, factor: usize) -> Image {
+    if factor <= 1 {
+        return image.clone();
+    }
+
+    let rows = image
+        .rows()
+        .flat_map(|row| {
+            let scaled: Vec<Rgb> = row.iter().flat_map(|&pixel| std::iter::repeat_n(pixel, factor)).collect();
+            std::iter::repeat_n(scaled, factor)
+        })
+        .collect();
+    Grid::from_rows(rows)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+    Gif,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+            Format::Gif => "gif",
+        }
+    }
+
+    pub fn encode(self, image: &Image) -> io::Result<Vec<u8>> {
+        match self {
+            Format::Ppm => Ok(encode::ppm(image)),
+            Format::Png => Ok(encode::png(image)),
+            Format::Gif => encode::gif(image),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ppm" => Ok(Format::Ppm),
+            "png" => Ok(Format::Png),
+            "gif" => Ok(Format::Gif),
+            _ => Err(format!("invalid image format `{}`, expected ppm, png or gif", value)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Writes `image` scaled by `factor`, in the format given by the extension of `path`.
+pub fn save(image: &Image, path: &Path, factor: usize) -> io::Result<()> {
+    let format: Format = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .parse()
+        .map_err(|error: String| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    fs::write(path, format.encode(&scale(image, factor))?)
+}
+
+/// A sequence of frames, written as numbered `00000.png`, `00001.png`... files into a directory
+/// or, for [`Format::Gif`], as one animated GIF.
+pub struct Frames {
+    target: Target,
+    scale: usize,
+    count: usize,
+}
+
+enum Target {
+    Numbered { directory: PathBuf, format: Format },
+    Gif { path: PathBuf, file: Option<File>, writer: Option<GifWriter<BufWriter<File>>> },
+}
+
+impl Frames {
+    /// Frames at `path`, a directory for numbered frames and `path.gif` for an animation. The
+    /// directory or file is created right away.
+    pub fn create(path: &Path, format: Format, scale: usize) -> io::Result<Frames> {
+        let target = match format {
+            Format::Gif => {
+                let path = path.with_extension("gif");
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                Target::Gif { file: Some(File::create(&path)?), path, writer: None }
+            }
+            format => {
+                fs::create_dir_all(path)?;
+                Target::Numbered { directory: path.to_path_buf(), format }
+            }
+        };
+
+        Ok(Frames { target, scale: scale.max(1), count: 0 })
+    }
+
+    pub fn push(&mut self, image: &Image) -> io::Result<()> {
+        let image = scale(image, self.scale);
+        match &mut self.target {
+            Target::Numbered { directory, format } => {
+                let path = directory.join(format!("{:05}.{}", self.count, format.extension()));
+                fs::write(path, format.encode(&image)?)?;
+            }
+            Target::Gif { file, writer, .. } => {
+                if let Some(file) = file.take() {
+                    *writer = Some(GifWriter::new(BufWriter::new(file), image.width(), image.height(), GIF_FRAME_DELAY)?);
+                }
+                writer.as_mut().expect("the GIF writer is created with the first frame").frame(&image)?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Where the frames go.
+    pub fn path(&self) -> &Path {
+        match &self.target {
+            Target::Numbered { directory, .. } => directory,
+            Target::Gif { path, .. } => path,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Completes the GIF, or removes it when it got no frames, and returns the number of frames
+    /// written.
+    pub fn finish(self) -> io::Result<usize> {
+        match self.target {
+            Target::Gif { writer: Some(writer), .. } => drop(writer.finish()?),
+            Target::Gif { path, writer: None, .. } => fs::remove_file(path)?,
+            Target::Numbered { .. } => {}
+        }
+        Ok(self.count)
+    }
+}
+
+/// Where the runner wants simulations recorded, see [`Recorder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameConfig {
+    pub directory: PathBuf,
+    pub format: Format,
+    pub scale: usize,
+}
+
+static FRAME_CONFIG: OnceLock<FrameConfig> = OnceLock::new();
+
+/// Turns recording on for the rest of the process; only the first call counts.
+pub fn record_frames(config: FrameConfig) {
+    let _ = FRAME_CONFIG.set(config);
+}
+
+/// A simulation whose frames were written, for the runner to point at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub day: u8,
+    pub path: PathBuf,
+    pub frames: usize,
+}
+
+/// Finished recordings by path, so a simulation run again only shows up once.
+static RECORDINGS: Mutex<BTreeMap<PathBuf, Recording>> = Mutex::new(BTreeMap::new());
+
+/// Removes and returns the recordings `day` finished so far.
+pub fn take_recordings(day: u8) -> Vec<Recording> {
+    let mut recordings = RECORDINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (taken, kept): (Vec<Recording>, Vec<Recording>) = std::mem::take(&mut *recordings).into_values().partition(|recording| recording.day == day);
+    *recordings = kept.into_iter().map(|recording| (recording.path.clone(), recording)).collect();
+    taken
+}
+
+/// The frames of one simulation of a day, written only when the runner turned recording on.
+///
+/// Days create one where their simulation starts and hand it a closure per step, which is not
+/// even called when nothing is recorded. The frames are completed when the recorder is dropped;
+/// problems writing them are logged as errors and stop the recording but never the day. Finished
+/// recordings are kept for the runner to report, see [`take_recordings`].
+pub struct Recorder {
+    frames: Option<Frames>,
+    every: usize,
+    steps: usize,
+    day: u8,
+}
+
+impl Recorder {
+    /// A recorder writing to `dayNN-name` inside the configured directory.
+    pub fn new(day: u8, name: &str) -> Recorder {
+        let frames = FRAME_CONFIG.get().and_then(|config| {
+            let path = config.directory.join(format!("day{:02}-{}", day, name));
+            Frames::create(&path, config.format, config.scale)
+                .inspect_err(|error| log::error!("Day {}: cannot record frames to {}: {}", day, path.display(), error))
+                .ok()
+        });
+
+        Recorder { frames, every: 1, steps: 0, day }
+    }
+
+    /// Only keeps every `every`th step, for long simulations.
+    pub fn every(mut self, every: usize) -> Recorder {
+        self.every = every.max(1);
+        self
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.frames.is_some()
+    }
+
+    pub fn frame(&mut self, draw: impl FnOnce() -> Image) {
+        let step = self.steps;
+        self.steps += 1;
+        let Some(frames) = self.frames.as_mut().filter(|_| step.is_multiple_of(self.every)) else {
+            return;
+        };
+
+        if let Err(error) = frames.push(&draw()) {
+            log::error!("Day {}: stopped recording frames to {}: {}", self.day, frames.path().display(), error);
+            self.frames = None;
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(frames) = self.frames.take() {
+            let path = frames.path().to_path_buf();
+            match frames.finish() {
+                Ok(frames) => {
+                    let recording = Recording { day: self.day, path: path.clone(), frames };
+                    RECORDINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(path, recording);
+                }
+                Err(error) => log::error!("Day {}: cannot finish frames in {}: {}", self.day, path.display(), error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::*;
+    use std::assert_eq;
+
+    const RED: Rgb = [255, 0, 0];
+    const BLACK: Rgb = [0, 0, 0];
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::parse("#.\n.#", "a tile", Some).unwrap();
+        let image = render(&grid, &Palette::new(BLACK).with('#', RED));
+
+        assert_eq!(image.cells(), &[RED, BLACK, BLACK, RED]);
+
+        let points = render_points([Point::new(1, 0), Point::new(-1, 0), Point::new(5, 5)], 2, 2, &Palette::new(BLACK).with(true, RED));
+        assert_eq!(points.cells(), &[BLACK, RED, BLACK, BLACK]);
+    }
+
+    #[test]
+    fn test_scale() {
+        let image = Image::new(2, 1, vec![RED, BLACK]);
+        let scaled = scale(&image, 2);
+
+        assert_eq!((scaled.width(), scaled.height()), (4, 2));
+        assert_eq!(scaled.row(1), &[RED, RED, BLACK, BLACK]);
+        assert_eq!(scale(&image, 0), image);
+    }
+
+    #[test]
+    fn test_frames() {
+        let directory = std::env::temp_dir().join(format!("aoc2024-frames-{}", std::process::id()));
+        let image = Image::new(2, 1, vec![RED, BLACK]);
+
+        let mut numbered = Frames::create(&directory.join("numbered"), Format::Ppm, 3).unwrap();
+        numbered.push(&image).unwrap();
+        numbered.push(&image).unwrap();
+        assert_eq!(numbered.finish().unwrap(), 2);
+        assert_eq!(fs::read(directory.join("numbered/00001.ppm")).unwrap()[..11], *b"P6\n6 3\n255\n");
+
+        let mut animation = Frames::create(&directory.join("animation"), Format::Gif, 1).unwrap();
+        animation.push(&image).unwrap();
+        assert!(animation.push(&Image::filled(3, 3, RED)).is_err());
+        assert_eq!(animation.finish().unwrap(), 1);
+        assert_eq!(fs::read(directory.join("animation.gif")).unwrap().last(), Some(&0x3B));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("png".parse(), Ok(Format::Png));
+        assert!("bmp".parse::<Format>().is_err());
+        assert_eq!(Format::Gif.to_string(), "gif");
+    }
+}
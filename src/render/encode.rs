@@ -0,0 +1,382 @@
+//! The image formats of [`render`](crate::render), written with nothing but the standard
+//! library. PNG data is a single deflate block with the fixed Huffman codes, which is plenty for
+//! the large flat areas of puzzle grids; GIF frames each carry their own colour table so an
+//! animation can be written one frame at a time.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use crate::render::{Image, Rgb};
+
+pub fn ppm(image: &Image) -> Vec<u8> {
+    let mut bytes = format!("P6\n{} {}\n255\n", image.width(), image.height()).into_bytes();
+    bytes.extend(image.cells().iter().flatten());
+    bytes
+}
+
+pub fn png(image: &Image) -> Vec<u8> {
+    let mut scanlines = Vec::with_capacity((image.width() * 3 + 1) * image.height());
+    for row in image.rows() {
+        scanlines.push(0); // no filter
+        scanlines.extend(row.iter().flatten());
+    }
+
+    let mut header = Vec::new();
+    header.extend((image.width() as u32).to_be_bytes());
+    header.extend((image.height() as u32).to_be_bytes());
+    header.extend([8, 2, 0, 0, 0]); // 8 bit RGB, not interlaced
+
+    let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut bytes, b"IHDR", &header);
+    chunk(&mut bytes, b"IDAT", &zlib(&scanlines));
+    chunk(&mut bytes, b"IEND", &[]);
+    bytes
+}
+
+/// A single frame GIF. Fails on images with more than 256 colours.
+pub fn gif(image: &Image) -> io::Result<Vec<u8>> {
+    let mut writer = GifWriter::new(Vec::new(), image.width(), image.height(), 0)?;
+    writer.frame(image)?;
+    writer.finish()
+}
+
+/// Writes an animated GIF that loops forever, one frame at a time.
+pub struct GifWriter<W: Write> {
+    out: W,
+    width: usize,
+    height: usize,
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Starts an animation of `width` x `height` frames shown for `delay` hundredths of a second.
+    pub fn new(mut out: W, width: usize, height: usize, delay: u16) -> io::Result<GifWriter<W>> {
+        let size = |length: usize| {
+            u16::try_from(length).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "a GIF is at most 65535 pixels wide and high"))
+        };
+
+        out.write_all(b"GIF89a")?;
+        out.write_all(&size(width)?.to_le_bytes())?;
+        out.write_all(&size(height)?.to_le_bytes())?;
+        out.write_all(&[0, 0, 0])?; // no global colour table
+        out.write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")?; // loop forever
+
+        Ok(GifWriter { out, width, height, delay })
+    }
+
+    pub fn frame(&mut self, image: &Image) -> io::Result<()> {
+        if (image.width(), image.height()) != (self.width, self.height) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "all frames of a GIF need the same size"));
+        }
+
+        let mut colours: Vec<Rgb> = Vec::new();
+        let mut index_of: HashMap<Rgb, u8> = HashMap::new();
+        let mut indices = Vec::with_capacity(image.cells().len());
+        for &colour in image.cells() {
+            let index = match index_of.get(&colour) {
+                Some(&index) => index,
+                None if colours.len() < 256 => {
+                    let index = colours.len() as u8;
+                    index_of.insert(colour, index);
+                    colours.push(colour);
+                    index
+                }
+                None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "a GIF frame has at most 256 colours")),
+            };
+            indices.push(index);
+        }
+        let table_bits = colours.len().max(2).next_power_of_two().trailing_zeros();
+
+        self.out.write_all(&[0x21, 0xF9, 4, 0])?;
+        self.out.write_all(&self.delay.to_le_bytes())?;
+        self.out.write_all(&[0, 0])?;
+
+        self.out.write_all(&[0x2C, 0, 0, 0, 0])?;
+        self.out.write_all(&(self.width as u16).to_le_bytes())?;
+        self.out.write_all(&(self.height as u16).to_le_bytes())?;
+        self.out.write_all(&[0x80 | (table_bits - 1) as u8])?; // local colour table
+        for index in 0..1 << table_bits {
+            self.out.write_all(colours.get(index).unwrap_or(&[0, 0, 0]))?;
+        }
+
+        let min_code_size = table_bits.max(2);
+        self.out.write_all(&[min_code_size as u8])?;
+        for block in lzw(&indices, min_code_size).chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0])
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(&[0x3B])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Packs codes least significant bit first, as both deflate and GIF do.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        self.buffer |= (value as u64) << self.bits;
+        self.bits += count;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Huffman codes go most significant bit first.
+    fn write_code(&mut self, code: u32, count: u32) {
+        self.write(code.reverse_bits() >> (32 - count), count);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let table: Vec<u32> = (0..256)
+        .map(|index| (0..8).fold(index, |crc, _| if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 }))
+        .collect();
+    !data.iter().fold(!0u32, |crc, &byte| table[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    bytes.extend(deflate(data));
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+const LENGTH_BASE: [u32; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u32; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u32; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u32; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+const WINDOW: usize = 32768;
+const HASH_BITS: u32 = 15;
+
+/// One fixed Huffman block, with greedy matches against the last position of every three bytes.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::default();
+    out.write(1, 1); // last block
+    out.write(1, 2); // fixed Huffman codes
+
+    let hash = |key: &[u8]| (u32::from_le_bytes([key[0], key[1], key[2], 0]).wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize;
+    let mut last_seen = vec![usize::MAX; 1 << HASH_BITS];
+    let mut position = 0;
+    while position < data.len() {
+        let candidate = data
+            .get(position..position + 3)
+            .map(|key| (key, last_seen[hash(key)]))
+            .filter(|&(key, candidate)| candidate < position && position - candidate <= WINDOW && data[candidate..candidate + 3] == *key)
+            .map(|(_, candidate)| candidate);
+
+        let step = match candidate {
+            Some(candidate) => {
+                let length = data[position..].iter().zip(&data[candidate..]).take(258).take_while(|(a, b)| a == b).count();
+                write_match(&mut out, length as u32, (position - candidate) as u32);
+                length
+            }
+            None => {
+                write_symbol(&mut out, data[position] as u32);
+                1
+            }
+        };
+        for seen in position..position + step {
+            if let Some(key) = data.get(seen..seen + 3) {
+                last_seen[hash(key)] = seen;
+            }
+        }
+        position += step;
+    }
+
+    write_symbol(&mut out, 256);
+    out.finish()
+}
+
+fn write_symbol(out: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => out.write_code(0x30 + symbol, 8),
+        144..=255 => out.write_code(0x190 + symbol - 144, 9),
+        256..=279 => out.write_code(symbol - 256, 7),
+        _ => out.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(out: &mut BitWriter, length: u32, distance: u32) {
+    let index = LENGTH_BASE.iter().rposition(|&base| base <= length).unwrap();
+    write_symbol(out, 257 + index as u32);
+    out.write(length - LENGTH_BASE[index], LENGTH_EXTRA[index]);
+
+    let index = DISTANCE_BASE.iter().rposition(|&base| base <= distance).unwrap();
+    out.write_code(index as u32, 5);
+    out.write(distance - DISTANCE_BASE[index], DISTANCE_EXTRA[index]);
+}
+
+/// Variable length LZW as GIF wants it, starting over once the code table is full.
+fn lzw(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1u32 << min_code_size;
+    let end = clear + 1;
+
+    let mut out = BitWriter::default();
+    let mut table: HashMap<(u32, u8), u32> = HashMap::new();
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end + 1;
+    let mut prefix: Option<u32> = None;
+
+    out.write(clear, code_size);
+    for &index in indices {
+        let Some(current) = prefix else {
+            prefix = Some(index as u32);
+            continue;
+        };
+        if let Some(&code) = table.get(&(current, index)) {
+            prefix = Some(code);
+            continue;
+        }
+
+        out.write(current, code_size);
+        if next_code == 4096 {
+            out.write(clear, code_size);
+            table.clear();
+            code_size = min_code_size + 1;
+            next_code = end + 1;
+        } else {
+            table.insert((current, index), next_code);
+            next_code += 1;
+            if next_code > 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        }
+        prefix = Some(index as u32);
+    }
+
+    if let Some(current) = prefix {
+        out.write(current, code_size);
+    }
+    out.write(end, code_size);
+    out.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::encode::*;
+    use std::assert_eq;
+
+    /// A plain GIF LZW decoder, to check the encoder against.
+    fn decode_lzw(bytes: &[u8], min_code_size: u32) -> Vec<u8> {
+        let mut bits = bytes.iter().flat_map(|&byte| (0..8).map(move |bit| (byte >> bit) as u32 & 1));
+        let mut read = |count: u32| (0..count).map(|bit| bits.next().unwrap() << bit).sum::<u32>();
+
+        let clear = 1u32 << min_code_size;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = Vec::new();
+
+        loop {
+            let code = read(code_size);
+            if code == clear {
+                table = (0..clear).map(|index| vec![index as u8]).chain([vec![], vec![]]).collect();
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return output;
+            }
+
+            let entry = match table.get(code as usize) {
+                Some(entry) => entry.clone(),
+                None => {
+                    let mut entry = previous.clone().unwrap();
+                    entry.push(entry[0]);
+                    entry
+                }
+            };
+            if let Some(mut longer) = previous {
+                longer.push(entry[0]);
+                if table.len() < 4096 {
+                    table.push(longer);
+                }
+            }
+            if table.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        let mut state = 12345u32;
+        let indices: Vec<u8> = (0..60000)
+            .map(|index| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                if index % 1000 < 500 { (index / 97 % 4) as u8 } else { (state >> 16) as u8 % 4 }
+            })
+            .collect();
+
+        assert_eq!(decode_lzw(&lzw(&indices, 2), 2), indices);
+        assert_eq!(decode_lzw(&lzw(&[], 2), 2), Vec::<u8>::new());
+        assert_eq!(decode_lzw(&lzw(&[7, 7, 7, 7, 7, 0, 255], 8), 8), vec![7, 7, 7, 7, 7, 0, 255]);
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_headers() {
+        let image = Image::filled(3, 2, [255, 0, 0]);
+
+        assert_eq!(ppm(&image)[..11], *b"P6\n3 2\n255\n");
+        assert_eq!(ppm(&image).len(), 11 + 18);
+
+        let png = png(&image);
+        assert_eq!(png[..8], *b"\x89PNG\r\n\x1a\n");
+        assert_eq!(png[12..24], *b"IHDR\x00\x00\x00\x03\x00\x00\x00\x02");
+        assert_eq!(png[png.len() - 12..], *b"\x00\x00\x00\x00IEND\xAE\x42\x60\x82");
+
+        let animation = gif(&image).unwrap();
+        assert_eq!(animation[..10], *b"GIF89a\x03\x00\x02\x00");
+        assert_eq!(animation.last(), Some(&0x3B));
+        assert!(gif(&Image::new(300, 1, (0..300).map(|n| [n as u8, (n / 256) as u8, 0]).collect())).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::render::{Palette, Rgb};
+use crate::utils::{Grid, Position};
+
+/// How many of the actor's last positions stay highlighted, fading out with age.
+pub const TRAIL_LENGTH: usize = 24;
+
+const ACTOR_COLOUR: Rgb = [255, 200, 0];
+const TRAIL_COLOUR: Rgb = [90, 60, 140];
+const CAPTION_COLOUR: Rgb = [150, 150, 150];
+
+/// One step of a simulation: the map as characters and where the actor (robot, guard,
+/// reindeer...) stands, if there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub grid: Grid<char>,
+    pub actor: Option<Position>,
+}
+
+impl Scene {
+    pub fn new(grid: Grid<char>) -> Scene {
+        Scene { grid, actor: None }
+    }
+
+    pub fn with_actor(mut self, position: Position) -> Scene {
+        self.actor = Some(position);
+        self
+    }
+}
+
+/// How fast the runner wants simulations drawn, see [`Visualizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisualizeConfig {
+    /// Frames per second, 0 for as fast as the terminal keeps up.
+    pub fps: u32,
+}
+
+impl VisualizeConfig {
+    fn frame_time(self) -> Duration {
+        match self.fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        }
+    }
+}
+
+static VISUALIZE_CONFIG: OnceLock<VisualizeConfig> = OnceLock::new();
+
+/// Turns terminal visualization on for the rest of the process; only the first call counts.
+pub fn visualize(config: VisualizeConfig) {
+    let _ = VISUALIZE_CONFIG.set(config);
+}
+
+/// Draws the steps of one simulation of a day on stderr, only when the runner turned
+/// visualization on.
+///
+/// Like a [`Recorder`](crate::render::Recorder) it takes a closure per step that is not called
+/// when nothing is shown. Every frame is drawn over the previous one by moving the cursor back
+/// up, and frames are paced to the configured rate.
+pub struct Visualizer {
+    frame_time: Option<Duration>,
+    palette: Palette<char>,
+    every: usize,
+    steps: usize,
+    day: u8,
+    name: String,
+    trail: VecDeque<Position>,
+    drawn_lines: usize,
+    last_frame: Option<Instant>,
+}
+
+impl Visualizer {
+    /// A visualizer colouring the characters of its scenes with `palette`.
+    pub fn new(day: u8, name: &str, palette: Palette<char>) -> Visualizer {
+        Visualizer {
+            frame_time: VISUALIZE_CONFIG.get().map(|config| config.frame_time()),
+            palette,
+            every: 1,
+            steps: 0,
+            day,
+            name: name.to_string(),
+            trail: VecDeque::new(),
+            drawn_lines: 0,
+            last_frame: None,
+        }
+    }
+
+    /// Only draws every `every`th step, for long simulations.
+    pub fn every(mut self, every: usize) -> Visualizer {
+        self.every = every.max(1);
+        self
+    }
+
+    pub fn is_visualizing(&self) -> bool {
+        self.frame_time.is_some()
+    }
+
+    pub fn frame(&mut self, draw: impl FnOnce() -> Scene) {
+        let step = self.steps;
+        self.steps += 1;
+        let Some(frame_time) = self.frame_time.filter(|_| step.is_multiple_of(self.every)) else {
+            return;
+        };
+
+        let scene = draw();
+        let caption = format!("Day {} {}: step {}", self.day, self.name, step);
+        let mut text = String::new();
+        if self.drawn_lines == 0 {
+            text.push_str("\x1b[?25l");
+        } else {
+            text.push_str(&format!("\x1b[{}F", self.drawn_lines));
+        }
+        text.push_str(&draw_scene(&scene, &self.palette, &self.trail, &caption));
+        self.drawn_lines = scene.grid.height() + 1;
+
+        if let Some(actor) = scene.actor.filter(|&actor| self.trail.back() != Some(&actor)) {
+            self.trail.push_back(actor);
+            if self.trail.len() > TRAIL_LENGTH {
+                self.trail.pop_front();
+            }
+        }
+
+        if let Some(last_frame) = self.last_frame {
+            thread::sleep(frame_time.saturating_sub(last_frame.elapsed()));
+        }
+        if io::stderr().lock().write_all(text.as_bytes()).is_err() {
+            self.frame_time = None;
+        }
+        self.last_frame = Some(Instant::now());
+    }
+}
+
+impl Drop for Visualizer {
+    fn drop(&mut self) {
+        if self.drawn_lines > 0 {
+            let _ = io::stderr().lock().write_all(b"\x1b[0m\x1b[?25h");
+        }
+    }
+}
+
+/// The colours of one character on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Style {
+    foreground: Rgb,
+    background: Option<Rgb>,
+    bold: bool,
+}
+
+impl Style {
+    fn escape(self) -> String {
+        let [r, g, b] = self.foreground;
+        let mut escape = format!("\x1b[0;{}38;2;{};{};{}", if self.bold { "1;" } else { "" }, r, g, b);
+        if let Some([r, g, b]) = self.background {
+            escape.push_str(&format!(";48;2;{};{};{}", r, g, b));
+        }
+        escape.push('m');
+        escape
+    }
+}
+
+/// `from` moved towards `to` by `amount` out of 255.
+fn blend(from: Rgb, to: Rgb, amount: u8) -> Rgb {
+    let mix = |from: u8, to: u8| ((from as u32 * (255 - amount as u32) + to as u32 * amount as u32) / 255) as u8;
+    [mix(from[0], to[0]), mix(from[1], to[1]), mix(from[2], to[2])]
+}
+
+/// The caption and the rows of `scene`, each line cleared to its end, the actor highlighted and
+/// the `trail` (oldest first) fading into the background.
+fn draw_scene(scene: &Scene, palette: &Palette<char>, trail: &VecDeque<Position>, caption: &str) -> String {
+    let mut backgrounds: Grid<Option<Rgb>> = Grid::filled(scene.grid.width(), scene.grid.height(), None);
+    for (age, &position) in trail.iter().rev().enumerate() {
+        let fade = (age * 200 / TRAIL_LENGTH) as u8;
+        if let Some(background) = backgrounds.get_mut(position) {
+            background.get_or_insert(blend(TRAIL_COLOUR, [0, 0, 0], fade));
+        }
+    }
+
+    let caption_style = Style { foreground: CAPTION_COLOUR, background: None, bold: false };
+    let mut text = format!("{}{}\x1b[0m\x1b[K\n", caption_style.escape(), caption);
+    for (row, cells) in scene.grid.rows().enumerate() {
+        let mut current = None;
+        for (column, &symbol) in cells.iter().enumerate() {
+            let style = if scene.actor == Some((row, column)) {
+                Style { foreground: [0, 0, 0], background: Some(ACTOR_COLOUR), bold: true }
+            } else {
+                Style { foreground: palette.colour(&symbol), background: backgrounds[(row, column)], bold: false }
+            };
+            if current != Some(style) {
+                text.push_str(&style.escape());
+                current = Some(style);
+            }
+            text.push(symbol);
+        }
+        text.push_str("\x1b[0m\x1b[K\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::terminal::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_draw_scene() {
+        let grid = Grid::parse("#..\n.@.", "a tile", Some).unwrap();
+        let scene = Scene::new(grid).with_actor((1, 1));
+        let palette = Palette::new([200, 200, 200]).with('#', [100, 100, 100]);
+        let text = draw_scene(&scene, &palette, &VecDeque::from([(1, 0)]), "Day 6 guard: step 3");
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("Day 6 guard: step 3"));
+        assert_eq!(
+            lines[1],
+            "\x1b[0;38;2;100;100;100m#\x1b[0;38;2;200;200;200m..\x1b[0m\x1b[K"
+        );
+        assert_eq!(
+            lines[2],
+            "\x1b[0;38;2;200;200;200;48;2;90;60;140m.\x1b[0;1;38;2;0;0;0;48;2;255;200;0m@\x1b[0;38;2;200;200;200m.\x1b[0m\x1b[K"
+        );
+    }
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(blend([255, 0, 100], [0, 255, 100], 0), [255, 0, 100]);
+        assert_eq!(blend([255, 0, 100], [0, 255, 100], 255), [0, 255, 100]);
+        assert_eq!(VisualizeConfig { fps: 50 }.frame_time(), Duration::from_millis(20));
+        assert_eq!(VisualizeConfig { fps: 0 }.frame_time(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+use crate::answer::Answer;
+use crate::solution::Part;
+use crate::verify::Status;
+
+/// One executed part, as written to a JSON or CSV report. Parts of a day that panicked or timed
+/// out have no answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Option<Answer>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    pub status: Status,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("invalid report format `{}`, expected json or csv", value)),
+        }
+    }
+}
+
+/// Renders `records` in `format`; times are in nanoseconds and the status is lowercase.
+pub fn render(format: Format, records: &[Record]) -> String {
+    match format {
+        Format::Json => to_json(records),
+        Format::Csv => to_csv(records),
+    }
+}
+
+fn to_json(records: &[Record]) -> String {
+    let mut json = String::from("[\n");
+
+    for (index, record) in records.iter().enumerate() {
+        let answer = match &record.answer {
+            Some(Answer::Text(text)) => json_string(text),
+            Some(number) => number.to_string(),
+            None => "null".to_string(),
+        };
+        let separator = if index + 1 < records.len() { "," } else { "" };
+        writeln!(
+            json,
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"parse_time_ns\": {}, \"solve_time_ns\": {}, \"status\": \"{}\"}}{}",
+            record.day,
+            record.part,
+            answer,
+            record.parse_time.as_nanos(),
+            record.solve_time.as_nanos(),
+            status_name(record.status),
+            separator
+        ).unwrap();
+    }
+
+    json.push_str("]\n");
+    json
+}
+
+fn to_csv(records: &[Record]) -> String {
+    let mut csv = String::from("day,part,answer,parse_time_ns,solve_time_ns,status\n");
+
+    for record in records {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            csv_field(&record.answer.as_ref().map(Answer::to_string).unwrap_or_default()),
+            record.parse_time.as_nanos(),
+            record.solve_time.as_nanos(),
+            status_name(record.status)
+        ).unwrap();
+    }
+
+    csv
+}
+
+fn status_name(status: Status) -> String {
+    status.to_string().to_lowercase()
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            control if control.is_control() => write!(escaped, "\\u{:04x}", control as u32).unwrap(),
+            other => escaped.push(other),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::*;
+    use std::assert_eq;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 17,
+                part: Part::One,
+                answer: Some(Answer::from("4,6,3,5,6,3,5,2,1,0")),
+                parse_time: Duration::from_nanos(120),
+                solve_time: Duration::from_micros(15),
+                status: Status::Pass,
+            },
+            Record {
+                day: 17,
+                part: Part::Two,
+                answer: Some(Answer::from(117440)),
+                parse_time: Duration::from_nanos(120),
+                solve_time: Duration::from_millis(2),
+                status: Status::Missing,
+            },
+            Record {
+                day: 3,
+                part: Part::Two,
+                answer: None,
+                parse_time: Duration::ZERO,
+                solve_time: Duration::ZERO,
+                status: Status::Panicked,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_json() {
+        assert_eq!(render(Format::Json, &records()), "[
+  {\"day\": 17, \"part\": 1, \"answer\": \"4,6,3,5,6,3,5,2,1,0\", \"parse_time_ns\": 120, \"solve_time_ns\": 15000, \"status\": \"pass\"},
+  {\"day\": 17, \"part\": 2, \"answer\": 117440, \"parse_time_ns\": 120, \"solve_time_ns\": 2000000, \"status\": \"missing\"},
+  {\"day\": 3, \"part\": 2, \"answer\": null, \"parse_time_ns\": 0, \"solve_time_ns\": 0, \"status\": \"panicked\"}
+]
+");
+        assert_eq!(render(Format::Json, &[]), "[\n]\n");
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(render(Format::Csv, &records()), "day,part,answer,parse_time_ns,solve_time_ns,status
+17,1,\"4,6,3,5,6,3,5,2,1,0\",120,15000,pass
+17,2,117440,120,2000000,missing
+3,2,,0,0,panicked
+");
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+    }
+}
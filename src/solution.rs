@@ -0,0 +1,130 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use crate::alloc::{self, AllocStats};
+use crate::answer::Answer;
+use crate::bench::{self, BenchConfig, DayBench};
+use crate::parse::ParseError;
+
+/// A single Advent of Code day.
+///
+/// `parse` turns the raw puzzle input into whatever both parts work on, or reports where the
+/// input does not look like the puzzle describes. Days that operate directly on the text use
+/// `&str` as their input and return it unchanged.
+///
+/// Parsing is optional for a day but `parse` has no default: the default would return the text
+/// itself, which only type checks for `type Input<'a> = &'a str`, and tying the two together
+/// takes an associated type default, which stable Rust does not have. A default behind a
+/// `where` bound on `Input` would have to be repeated by every caller of `parse`. Days without
+/// a parse step write the one line `Ok(input)`, see day 3.
+pub trait Solution {
+    const DAY: u8;
+    const NAME: &'static str;
+
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError>;
+
+    fn part_one(input: &Self::Input<'_>) -> Answer;
+
+    fn part_two(input: &Self::Input<'_>) -> Answer;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self.number() as usize - 1
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.number(), f)
+    }
+}
+
+/// The answer of one part and how long solving it took, parsing excluded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Answer,
+    pub solve_time: Duration,
+    /// What solving allocated, when profiling allocations.
+    pub alloc: AllocStats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayRun {
+    pub parse_time: Duration,
+    pub parse_alloc: AllocStats,
+    pub parts: Vec<PartRun>,
+}
+
+/// Object safe view of a [`Solution`], used by the registry in `lib.rs`.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u8;
+
+    fn name(&self) -> &'static str;
+
+    /// Parses `input` once and solves the requested `parts` in the given order.
+    fn run(&self, input: &str, parts: &[Part]) -> Result<DayRun, ParseError>;
+
+    /// Measures parsing and each of `parts` separately, plus the complete run as one sample.
+    fn bench(&self, input: &str, parts: &[Part], config: &BenchConfig) -> Result<DayBench, ParseError>;
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<DayRun, ParseError> {
+        let start_time = Instant::now();
+        let (input, parse_alloc) = alloc::measure(|| S::parse(input));
+        let input = input.map_err(|error| error.in_day(S::DAY))?;
+        let parse_time = start_time.elapsed();
+
+        let parts = parts.iter().map(|&part| {
+            let start_time = Instant::now();
+            let (answer, alloc) = alloc::measure(|| match part {
+                Part::One => S::part_one(&input),
+                Part::Two => S::part_two(&input),
+            });
+            PartRun { part, answer, solve_time: start_time.elapsed(), alloc }
+        }).collect();
+
+        Ok(DayRun { parse_time, parse_alloc, parts })
+    }
+
+    fn bench(&self, input: &str, parts: &[Part], config: &BenchConfig) -> Result<DayBench, ParseError> {
+        let parsed = S::parse(input).map_err(|error| error.in_day(S::DAY))?;
+        let parse = bench::measure(config, || S::parse(input));
+        let part_stats = parts.iter().map(|&part| {
+            let stats = match part {
+                Part::One => bench::measure(config, || S::part_one(&parsed)),
+                Part::Two => bench::measure(config, || S::part_two(&parsed)),
+            };
+            (part, stats)
+        }).collect();
+        let total = bench::measure(config, || self.run(input, parts));
+
+        Ok(DayBench { parse, parts: part_stats, total })
+    }
+}
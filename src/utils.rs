@@ -1,5 +1,26 @@
-
-pub fn get_middle_number(numbers: &Vec<i32>) -> Option<i32> {
+//! Grids, geometry, graphs and parsing helpers shared by the days.
+//!
+//! Two coordinate types meet in the grid days, with their components in opposite orders:
+//!
+//! - a [`Position`] is an index into a [`Grid`] or [`BitGrid`], `(row, column)`;
+//! - a [`Point`] is a signed point on the plane, `Point { x, y }`, so `x` is the column and `y`
+//!   the row.
+//!
+//! `(1, 69)` and `Point::new(69, 1)` are the same cell. Never convert between the two by hand;
+//! go through [`Point::from_position`] and [`Point::position`], which also rejects points with a
+//! negative coordinate. Puzzle inputs that give coordinates as `x,y` are read into a `Point`.
+
+pub mod bitgrid;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod parsing;
+
+pub use bitgrid::{BitGrid, StateSet};
+pub use geometry::{Direction, Point, Vec2};
+pub use grid::{Grid, Position};
+
+pub fn get_middle_number(numbers: &[i32]) -> Option<i32> {
     if numbers.is_empty() {
         return None; // Return None if the Vec is empty
     }
@@ -25,42 +46,3 @@ pub fn find_element<T, I>(collection: I, target: T) -> Option<T>
 {
     collection.into_iter().find(|&item| item == target)
 }
-
-pub fn parse_to_grid(input: &str) -> Vec<Vec<char>> {
-    let grid: Vec<Vec<char>> = input
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect();
-    grid
-}
-
-pub fn get_element_from_grid(collection: &Vec<Vec<char>>, coords: (usize, usize)) -> char
-{
-    collection[coords.0][coords.1]
-}
-
-pub fn count_char_in_grid(grid: &Vec<Vec<char>>, target: char) -> usize {
-    grid.iter()
-        .flat_map(|row| row.iter())
-        .filter(|&&c| c == target)
-        .count()
-}
-
-pub fn print_grid_with_delimiters(grid: &Vec<Vec<char>>) {
-    for row in grid {
-        println!("|{}|", row.iter().map(|&c| c.to_string()).collect::<Vec<_>>().join("|"));
-    }
-
-    println!("{}", "-".repeat(grid[0].len()));
-}
-
-pub fn find_coordinates(grid: Vec<Vec<char>>, target: char) -> Option<(usize, usize)> {
-    for (row_idx, row) in grid.iter().enumerate() {
-        for (col_idx, &cell) in row.iter().enumerate() {
-            if cell == target {
-                return Some((col_idx, row_idx));
-            }
-        }
-    }
-    None
-}
@@ -0,0 +1,279 @@
+//! Dense sets over the cells of a grid, one bit per cell (and state), for the visited sets of
+//! grid searches. Inserting and looking up is an index computation, clearing a whole set is a
+//! `memset` of `width * height / 64` words.
+
+use crate::utils::{Grid, Point, Position};
+
+/// The bits behind the sets, addressed by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bits {
+    words: Vec<u64>,
+}
+
+impl Bits {
+    fn new(len: usize) -> Bits {
+        Bits { words: vec![0; len.div_ceil(64)] }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Sets the bit, returning whether it was unset before.
+    fn set(&mut self, index: usize) -> bool {
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        let was_unset = *word & mask == 0;
+        *word |= mask;
+        was_unset
+    }
+
+    /// Unsets the bit, returning whether it was set before.
+    fn unset(&mut self, index: usize) -> bool {
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        let was_set = *word & mask != 0;
+        *word &= !mask;
+        was_set
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// The indices of the set bits, in increasing order.
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(index * 64 + bit)
+            })
+        })
+    }
+}
+
+/// A set of positions of a `width` x `height` grid.
+///
+/// Looking up a position outside the grid finds nothing; inserting one panics, like indexing a
+/// [`Grid`] would.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    bits: Bits,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        BitGrid { width, height, bits: Bits::new(width * height) }
+    }
+
+    /// An empty set the size of `grid`.
+    pub fn for_grid<T>(grid: &Grid<T>) -> BitGrid {
+        BitGrid::new(grid.width(), grid.height())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, (row, column): Position) -> Option<usize> {
+        (row < self.height && column < self.width).then_some(row * self.width + column)
+    }
+
+    fn index_or_panic(&self, position: Position) -> usize {
+        self.index(position).unwrap_or_else(|| {
+            panic!("position {:?} is outside the {}x{} set", position, self.width, self.height)
+        })
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.index(position).is_some_and(|index| self.bits.get(index))
+    }
+
+    /// Whether `point` is in the set; points with negative coordinates never are.
+    pub fn contains_point(&self, point: Point) -> bool {
+        point.position().is_some_and(|position| self.contains(position))
+    }
+
+    /// Adds `position`, returning whether it was not in the set yet.
+    pub fn insert(&mut self, position: Position) -> bool {
+        let index = self.index_or_panic(position);
+        self.bits.set(index)
+    }
+
+    /// Removes `position`, returning whether it was in the set.
+    pub fn remove(&mut self, position: Position) -> bool {
+        self.index(position).is_some_and(|index| self.bits.unset(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.words.iter().all(|&word| word == 0)
+    }
+
+    /// The positions in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.bits.ones().map(|index| (index / self.width, index % self.width))
+    }
+}
+
+impl Extend<Position> for BitGrid {
+    fn extend<I: IntoIterator<Item = Position>>(&mut self, positions: I) {
+        for position in positions {
+            self.insert(position);
+        }
+    }
+}
+
+/// A set of states on a `width` x `height` grid, a position with one of `states` small numbers
+/// such as a [`Direction::index`](crate::utils::Direction::index).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSet {
+    width: usize,
+    height: usize,
+    states: usize,
+    bits: Bits,
+}
+
+impl StateSet {
+    pub fn new(width: usize, height: usize, states: usize) -> StateSet {
+        StateSet { width, height, states, bits: Bits::new(width * height * states) }
+    }
+
+    /// An empty set the size of `grid`.
+    pub fn for_grid<T>(grid: &Grid<T>, states: usize) -> StateSet {
+        StateSet::new(grid.width(), grid.height(), states)
+    }
+
+    fn index(&self, (row, column): Position, state: usize) -> Option<usize> {
+        (row < self.height && column < self.width && state < self.states)
+            .then_some((row * self.width + column) * self.states + state)
+    }
+
+    fn index_or_panic(&self, position: Position, state: usize) -> usize {
+        self.index(position, state).unwrap_or_else(|| {
+            panic!("state {:?}/{} is outside the {}x{}x{} set", position, state, self.width, self.height, self.states)
+        })
+    }
+
+    pub fn contains(&self, position: Position, state: usize) -> bool {
+        self.index(position, state).is_some_and(|index| self.bits.get(index))
+    }
+
+    /// Adds the state, returning whether it was not in the set yet.
+    pub fn insert(&mut self, position: Position, state: usize) -> bool {
+        let index = self.index_or_panic(position, state);
+        self.bits.set(index)
+    }
+
+    /// Removes the state, returning whether it was in the set.
+    pub fn remove(&mut self, position: Position, state: usize) -> bool {
+        self.index(position, state).is_some_and(|index| self.bits.unset(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.words.iter().all(|&word| word == 0)
+    }
+
+    /// The states in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, usize)> + '_ {
+        self.bits.ones().map(|index| {
+            let (cell, state) = (index / self.states, index % self.states);
+            ((cell / self.width, cell % self.width), state)
+        })
+    }
+
+    /// The positions with at least one state in the set.
+    pub fn positions(&self) -> BitGrid {
+        let mut positions = BitGrid::new(self.width, self.height);
+        positions.extend(self.iter().map(|(position, _)| position));
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::bitgrid::*;
+    use crate::utils::Direction;
+    use std::assert_eq;
+
+    #[test]
+    fn test_bit_grid() {
+        let mut set = BitGrid::new(70, 3);
+
+        assert!(set.insert((0, 0)));
+        assert!(set.insert((1, 69)));
+        assert!(!set.insert((1, 69)));
+        assert!(set.insert((2, 5)));
+        assert!(set.contains((1, 69)));
+        assert!(!set.contains((1, 68)));
+        assert!(!set.contains((3, 0)));
+        assert!(!set.contains((0, 70)));
+        assert!(set.contains_point(Point::from_position((1, 69))));
+        assert!(set.contains_point(Point::new(69, 1)));
+        assert!(!set.contains_point(Point::new(1, 69)));
+        assert!(!set.contains_point(Point::new(-1, 1)));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![(0, 0), (1, 69), (2, 5)]);
+
+        assert!(set.remove((0, 0)));
+        assert!(!set.remove((0, 0)));
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.iter().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 4x4 set")]
+    fn test_bit_grid_insert_outside() {
+        BitGrid::new(4, 4).insert((4, 0));
+    }
+
+    #[test]
+    fn test_state_set() {
+        let mut set = StateSet::new(5, 5, 4);
+
+        assert!(set.insert((2, 3), Direction::Left.index()));
+        assert!(set.insert((2, 3), Direction::Up.index()));
+        assert!(!set.insert((2, 3), Direction::Up.index()));
+        assert!(set.insert((4, 4), Direction::Down.index()));
+        assert!(set.contains((2, 3), Direction::Left.index()));
+        assert!(!set.contains((2, 3), Direction::Right.index()));
+        assert!(!set.contains((2, 3), 4));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![((2, 3), 0), ((2, 3), 3), ((4, 4), 2)]);
+        assert_eq!(set.positions().iter().collect::<Vec<_>>(), vec![(2, 3), (4, 4)]);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use crate::utils::Position;
+
+/// A signed point on the plane, `x` growing to the right and `y` growing downwards like the rows
+/// of a [`Grid`](crate::utils::Grid).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The difference between two points; the same type, named for readability.
+pub type Vec2 = Point;
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// The point of a grid `(row, column)` position.
+    pub fn from_position((row, column): Position) -> Point {
+        Point { x: column as i64, y: row as i64 }
+    }
+
+    /// The grid position of this point, `None` when a coordinate is negative.
+    pub fn position(self) -> Option<Position> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+
+    pub fn manhattan(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The point one step away in `direction`.
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+
+    /// The four points sharing an edge with this one, clockwise from the one above.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |direction| self.step(direction))
+    }
+
+    /// Every point at exactly `distance` steps from this one, clockwise from the one above.
+    pub fn manhattan_ring(self, distance: i64) -> impl Iterator<Item = Point> {
+        let count = if distance == 0 { 1 } else { 4 * distance };
+        (0..count).map(move |index| {
+            if distance == 0 {
+                return self;
+            }
+            let along = index % distance;
+            self + match index / distance {
+                0 => Point::new(along, along - distance),
+                1 => Point::new(distance - along, along),
+                2 => Point::new(-along, distance - along),
+                _ => Point::new(along - distance, -along),
+            }
+        })
+    }
+
+    /// Wraps the point around a torus of `size`, so both coordinates end up in `0..size`.
+    pub fn wrap(self, size: Vec2) -> Point {
+        Point { x: self.x.rem_euclid(size.x), y: self.y.rem_euclid(size.y) }
+    }
+
+    /// `self + offset` on a torus of `size`.
+    pub fn wrapping_add(self, offset: Vec2, size: Vec2) -> Point {
+        (self + offset).wrap(size)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point { x: self.x - other.x, y: self.y - other.y }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point { x: self.x * factor, y: self.y * factor }
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point { x: -self.x, y: -self.y }
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// One of the four directions on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from up.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// Parses one of the arrows `^>v<`.
+    pub fn from_arrow(symbol: char) -> Option<Direction> {
+        match symbol {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// Parses one of the letters `UDLR`.
+    pub fn from_letter(symbol: char) -> Option<Direction> {
+        match symbol {
+            'U' => Some(Direction::Up),
+            'R' => Some(Direction::Right),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    /// The position of the direction in [`Direction::ALL`], handy for indexing per direction.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.arrow())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::geometry::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point::new(2, -3);
+        let b = Point::new(-1, 5);
+
+        assert_eq!(a + b, Point::new(1, 2));
+        assert_eq!(a - b, Point::new(3, -8));
+        assert_eq!(-a * 2, Point::new(-4, 6));
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(Point::from_position((4, 7)), Point::new(7, 4));
+        assert_eq!(Point::new(7, 4).position(), Some((4, 7)));
+        assert_eq!(Point::new(-1, 4).position(), None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let size = Point::new(11, 7);
+
+        assert_eq!(Point::new(2, 4).wrapping_add(Point::new(2, -3) * 5, size), Point::new(1, 3));
+        assert_eq!(Point::new(-12, 7).wrap(size), Point::new(10, 0));
+    }
+
+    #[test]
+    fn test_manhattan_ring() {
+        let centre = Point::new(3, 3);
+
+        assert_eq!(centre.manhattan_ring(0).collect::<Vec<_>>(), vec![centre]);
+        assert_eq!(
+            centre.manhattan_ring(1).collect::<Vec<_>>(),
+            centre.neighbours4().collect::<Vec<_>>()
+        );
+
+        let ring: Vec<Point> = centre.manhattan_ring(3).collect();
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|&point| point.manhattan(centre) == 3));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::from_arrow('^'), Some(Direction::Up));
+        assert_eq!(Direction::from_arrow('U'), None);
+        assert_eq!(Direction::from_letter('L'), Some(Direction::Left));
+        assert_eq!(Direction::from_letter('<'), None);
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Point::ORIGIN.step(Direction::Down), Point::new(0, 1));
+        assert_eq!(Direction::ALL.map(Direction::arrow), ['^', '>', 'v', '<']);
+    }
+}
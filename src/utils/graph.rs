@@ -0,0 +1,614 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use crate::utils::{BitGrid, Direction, Position, StateSet};
+
+/// A directed graph given by the neighbours of every node and the cost of stepping to them.
+///
+/// Unweighted graphs give every edge a cost of 1. BFS and DFS ignore the costs, Dijkstra and A*
+/// add them up.
+pub trait Graph {
+    type Node: Copy + Eq + Hash;
+
+    /// The nodes one edge away from `node`, with the cost of that edge.
+    fn neighbours(&self, node: Self::Node) -> impl Iterator<Item = (Self::Node, u64)>;
+
+    /// An empty set for the nodes a search has visited or settled. A `HashSet` fits any graph;
+    /// graphs over grid positions can hand out a dense set, see [`with_node_set`].
+    fn node_set(&self) -> impl NodeSet<Self::Node> {
+        HashSet::new()
+    }
+
+    /// Breadth first search from `start`; distances count edges.
+    fn bfs(&self, start: Self::Node) -> Search<Self::Node> {
+        let mut search = Search::new(start);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            let distance = search.distances[&node];
+            for (next, _) in self.neighbours(node) {
+                if !search.contains(next) {
+                    search.reach(next, node, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        search
+    }
+
+    /// Depth first search from `start`, returning every reachable node in the order it was
+    /// first entered.
+    fn dfs(&self, start: Self::Node) -> Vec<Self::Node> {
+        let mut visited = self.node_set();
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            order.push(node);
+            stack.extend(self.neighbours(node).map(|(next, _)| next).filter(|&next| !visited.contains(next)));
+        }
+
+        order
+    }
+
+    /// Cheapest costs from `start` to every reachable node.
+    fn dijkstra(&self, start: Self::Node) -> Search<Self::Node> {
+        let mut search = Search::new(start);
+        let mut queue = BinaryHeap::from([Queued { priority: 0, node: start }]);
+        let mut settled = self.node_set();
+
+        while let Some(Queued { node, .. }) = queue.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            let cost = search.distances[&node];
+            for (next, step) in self.neighbours(node) {
+                let next_cost = cost + step;
+                if search.distance(next).is_none_or(|known| next_cost < known) {
+                    search.reach(next, node, next_cost);
+                    queue.push(Queued { priority: next_cost, node: next });
+                }
+            }
+        }
+
+        search
+    }
+
+    /// Cheapest costs from `start` like [`Graph::dijkstra`], keeping every predecessor through
+    /// which a node is reached at that cost, so all optimal paths can be counted or listed.
+    /// Edges should cost more than 0, or optimal paths may go round in circles.
+    fn all_shortest_paths(&self, start: Self::Node) -> ShortestPaths<Self::Node> {
+        let mut paths = ShortestPaths::new(start);
+        let mut queue = BinaryHeap::from([Queued { priority: 0, node: start }]);
+        let mut settled = self.node_set();
+
+        while let Some(Queued { node, .. }) = queue.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            let cost = paths.distances[&node];
+            for (next, step) in self.neighbours(node) {
+                let next_cost = cost + step;
+                match paths.distance(next) {
+                    Some(known) if next_cost > known => {}
+                    Some(known) if next_cost == known => {
+                        let predecessors = paths.predecessors.entry(next).or_default();
+                        if !predecessors.contains(&node) {
+                            predecessors.push(node);
+                        }
+                    }
+                    _ => {
+                        paths.distances.insert(next, next_cost);
+                        paths.predecessors.insert(next, vec![node]);
+                        queue.push(Queued { priority: next_cost, node: next });
+                    }
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// The cheapest path from `start` to the first node accepted by `is_goal`, guided by
+    /// `heuristic`. The heuristic must never overestimate the remaining cost and must not drop
+    /// by more than the cost of an edge, or the path found may not be the cheapest.
+    fn astar(
+        &self,
+        start: Self::Node,
+        is_goal: impl Fn(Self::Node) -> bool,
+        heuristic: impl Fn(Self::Node) -> u64,
+    ) -> Option<Path<Self::Node>> {
+        let mut search = Search::new(start);
+        let mut queue = BinaryHeap::from([Queued { priority: heuristic(start), node: start }]);
+        let mut settled = self.node_set();
+
+        while let Some(Queued { node, .. }) = queue.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            if is_goal(node) {
+                return search.path_to(node);
+            }
+            let cost = search.distances[&node];
+            for (next, step) in self.neighbours(node) {
+                let next_cost = cost + step;
+                if search.distance(next).is_none_or(|known| next_cost < known) {
+                    search.reach(next, node, next_cost);
+                    queue.push(Queued { priority: next_cost + heuristic(next), node: next });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The cheapest path from `start` to the first node accepted by `is_goal`.
+    fn shortest_path(&self, start: Self::Node, is_goal: impl Fn(Self::Node) -> bool) -> Option<Path<Self::Node>> {
+        self.astar(start, is_goal, |_| 0)
+    }
+}
+
+/// A path through a graph, from its start to its end node inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: u64,
+    pub nodes: Vec<N>,
+}
+
+/// Everything one search reached: the distance of every node from the start and the node it
+/// was reached from, from which paths are rebuilt.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    start: N,
+    distances: HashMap<N, u64>,
+    previous: HashMap<N, N>,
+}
+
+impl<N: Copy + Eq + Hash> Search<N> {
+    fn new(start: N) -> Search<N> {
+        Search { start, distances: HashMap::from([(start, 0)]), previous: HashMap::new() }
+    }
+
+    fn reach(&mut self, node: N, from: N, distance: u64) {
+        self.distances.insert(node, distance);
+        self.previous.insert(node, from);
+    }
+
+    pub fn start(&self) -> N {
+        self.start
+    }
+
+    pub fn contains(&self, node: N) -> bool {
+        self.distances.contains_key(&node)
+    }
+
+    pub fn distance(&self, node: N) -> Option<u64> {
+        self.distances.get(&node).copied()
+    }
+
+    /// Every reached node with its distance, in no particular order.
+    pub fn reached(&self) -> impl Iterator<Item = (N, u64)> + '_ {
+        self.distances.iter().map(|(&node, &distance)| (node, distance))
+    }
+
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    pub fn into_distances(self) -> HashMap<N, u64> {
+        self.distances
+    }
+
+    /// The path the search took from the start to `end`, `None` when `end` was not reached.
+    pub fn path_to(&self, end: N) -> Option<Path<N>> {
+        let cost = self.distance(end)?;
+        let mut nodes: Vec<N> = std::iter::successors(Some(end), |node| self.previous.get(node).copied()).collect();
+        nodes.reverse();
+        Some(Path { cost, nodes })
+    }
+}
+
+/// The result of [`Graph::all_shortest_paths`]: the cheapest cost of every reached node and all
+/// the nodes it is reached from at that cost, which form a DAG of the optimal paths.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N> {
+    start: N,
+    distances: HashMap<N, u64>,
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Copy + Eq + Hash> ShortestPaths<N> {
+    fn new(start: N) -> ShortestPaths<N> {
+        ShortestPaths { start, distances: HashMap::from([(start, 0)]), predecessors: HashMap::new() }
+    }
+
+    pub fn start(&self) -> N {
+        self.start
+    }
+
+    pub fn distance(&self, node: N) -> Option<u64> {
+        self.distances.get(&node).copied()
+    }
+
+    /// The nodes `node` is reached from on its optimal paths, empty for the start.
+    pub fn predecessors(&self, node: N) -> &[N] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Of `candidates`, the reached ones with the lowest cost, e.g. every state of a goal tile.
+    pub fn cheapest(&self, candidates: impl IntoIterator<Item = N>) -> Vec<N> {
+        let reached: Vec<(N, u64)> =
+            candidates.into_iter().filter_map(|node| Some((node, self.distance(node)?))).collect();
+        let best = reached.iter().map(|&(_, cost)| cost).min();
+        reached.into_iter().filter(|&(_, cost)| Some(cost) == best).map(|(node, _)| node).collect()
+    }
+
+    /// How many optimal paths lead from the start to any of `ends`.
+    pub fn count_paths(&self, ends: impl IntoIterator<Item = N>) -> u64 {
+        let ends: Vec<N> = ends.into_iter().filter(|end| self.distances.contains_key(end)).collect();
+        let mut counts: HashMap<N, u64> = HashMap::from([(self.start, 1)]);
+        let mut stack = ends.clone();
+
+        // a node is counted once all of its predecessors are
+        while let Some(&node) = stack.last() {
+            if counts.contains_key(&node) {
+                stack.pop();
+                continue;
+            }
+            let uncounted: Vec<N> =
+                self.predecessors(node).iter().copied().filter(|previous| !counts.contains_key(previous)).collect();
+            if uncounted.is_empty() {
+                counts.insert(node, self.predecessors(node).iter().map(|previous| counts[previous]).sum());
+                stack.pop();
+            } else {
+                stack.extend(uncounted);
+            }
+        }
+
+        ends.iter().map(|end| counts[end]).sum()
+    }
+
+    /// Every optimal path from the start to `end`.
+    pub fn paths_to(&self, end: N) -> Vec<Path<N>> {
+        let Some(cost) = self.distance(end) else {
+            return Vec::new();
+        };
+
+        let mut paths = Vec::new();
+        let mut partial = vec![vec![end]];
+        while let Some(nodes) = partial.pop() {
+            let first = nodes[nodes.len() - 1];
+            if first == self.start {
+                paths.push(Path { cost, nodes: nodes.into_iter().rev().collect() });
+                continue;
+            }
+            for &previous in self.predecessors(first) {
+                let mut longer = nodes.clone();
+                longer.push(previous);
+                partial.push(longer);
+            }
+        }
+
+        paths
+    }
+
+    /// Every node on at least one optimal path from the start to any of `ends`.
+    pub fn covered(&self, ends: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let mut covered = HashSet::new();
+        let mut stack: Vec<N> = ends.into_iter().filter(|&end| self.distances.contains_key(&end)).collect();
+
+        while let Some(node) = stack.pop() {
+            if covered.insert(node) {
+                stack.extend(self.predecessors(node).iter().copied());
+            }
+        }
+
+        covered
+    }
+}
+
+/// A graph stored as a list of outgoing edges per node.
+#[derive(Debug, Clone)]
+pub struct AdjacencyList<N> {
+    edges: HashMap<N, Vec<(N, u64)>>,
+}
+
+impl<N: Copy + Eq + Hash> AdjacencyList<N> {
+    pub fn new() -> AdjacencyList<N> {
+        AdjacencyList { edges: HashMap::new() }
+    }
+
+    /// Adds a node without edges; nodes are also added by the edges touching them.
+    pub fn add_node(&mut self, node: N) {
+        self.edges.entry(node).or_default();
+    }
+
+    /// Adds an edge of cost 1 in both directions.
+    pub fn add_edge(&mut self, a: N, b: N) {
+        self.add_weighted_edge(a, b, 1);
+    }
+
+    /// Adds an edge of `cost` in both directions.
+    pub fn add_weighted_edge(&mut self, a: N, b: N, cost: u64) {
+        self.add_arc(a, b, cost);
+        self.add_arc(b, a, cost);
+    }
+
+    /// Adds an edge of `cost` from `from` to `to` only. Adding an edge twice keeps the cheaper.
+    pub fn add_arc(&mut self, from: N, to: N, cost: u64) {
+        self.add_node(to);
+        let edges = self.edges.entry(from).or_default();
+        match edges.iter_mut().find(|(node, _)| *node == to) {
+            Some((_, known)) => *known = cost.min(*known),
+            None => edges.push((to, cost)),
+        }
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+        self.edges.keys().copied()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+impl<N: Copy + Eq + Hash> Default for AdjacencyList<N> {
+    fn default() -> Self {
+        AdjacencyList::new()
+    }
+}
+
+impl<N: Copy + Eq + Hash> Graph for AdjacencyList<N> {
+    type Node = N;
+
+    fn neighbours(&self, node: N) -> impl Iterator<Item = (N, u64)> {
+        self.edges.get(&node).into_iter().flatten().copied()
+    }
+}
+
+/// A graph whose edges are computed on demand, see [`from_fn`].
+#[derive(Debug, Clone)]
+pub struct Implicit<N, F> {
+    neighbours: F,
+    node: PhantomData<fn(N)>,
+}
+
+/// A graph given by a closure returning the neighbours of a node with the cost to reach them,
+/// for graphs such as grids or state spaces that are never stored.
+pub fn from_fn<N, F, I>(neighbours: F) -> Implicit<N, F>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    Implicit { neighbours, node: PhantomData }
+}
+
+impl<N, F, I> Graph for Implicit<N, F>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    type Node = N;
+
+    fn neighbours(&self, node: N) -> impl Iterator<Item = (N, u64)> {
+        (self.neighbours)(node).into_iter()
+    }
+}
+
+/// The set of nodes a search has seen.
+pub trait NodeSet<N> {
+    /// Adds `node`, returning whether it was not in the set yet.
+    fn insert(&mut self, node: N) -> bool;
+
+    fn contains(&self, node: N) -> bool;
+}
+
+impl<N: Eq + Hash> NodeSet<N> for HashSet<N> {
+    fn insert(&mut self, node: N) -> bool {
+        HashSet::insert(self, node)
+    }
+
+    fn contains(&self, node: N) -> bool {
+        HashSet::contains(self, &node)
+    }
+}
+
+impl NodeSet<Position> for BitGrid {
+    fn insert(&mut self, position: Position) -> bool {
+        BitGrid::insert(self, position)
+    }
+
+    fn contains(&self, position: Position) -> bool {
+        BitGrid::contains(self, position)
+    }
+}
+
+impl NodeSet<(Position, Direction)> for StateSet {
+    fn insert(&mut self, (position, direction): (Position, Direction)) -> bool {
+        StateSet::insert(self, position, direction.index())
+    }
+
+    fn contains(&self, (position, direction): (Position, Direction)) -> bool {
+        StateSet::contains(self, position, direction.index())
+    }
+}
+
+/// A graph searched with the node sets of a closure, see [`with_node_set`].
+#[derive(Debug, Clone)]
+pub struct WithNodeSet<G, F> {
+    graph: G,
+    node_set: F,
+}
+
+/// `graph` with `node_set` making the sets its searches track nodes in, typically a
+/// [`BitGrid`] or [`StateSet`] the size of the grid, which beat hashing positions by far.
+pub fn with_node_set<G, F, S>(graph: G, node_set: F) -> WithNodeSet<G, F>
+where
+    G: Graph,
+    F: Fn() -> S,
+    S: NodeSet<G::Node>,
+{
+    WithNodeSet { graph, node_set }
+}
+
+impl<G, F, S> Graph for WithNodeSet<G, F>
+where
+    G: Graph,
+    F: Fn() -> S,
+    S: NodeSet<G::Node>,
+{
+    type Node = G::Node;
+
+    fn neighbours(&self, node: G::Node) -> impl Iterator<Item = (G::Node, u64)> {
+        self.graph.neighbours(node)
+    }
+
+    fn node_set(&self) -> impl NodeSet<G::Node> {
+        (self.node_set)()
+    }
+}
+
+/// A node waiting in the priority queue, ordered so the lowest priority comes out first.
+struct Queued<N> {
+    priority: u64,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::graph::*;
+    use crate::utils::Grid;
+    use std::assert_eq;
+
+    fn weighted() -> AdjacencyList<char> {
+        let mut graph = AdjacencyList::new();
+        graph.add_weighted_edge('a', 'b', 7);
+        graph.add_weighted_edge('a', 'c', 2);
+        graph.add_weighted_edge('c', 'b', 3);
+        graph.add_weighted_edge('b', 'd', 1);
+        graph.add_arc('d', 'e', 4);
+        graph.add_node('f');
+        graph
+    }
+
+    #[test]
+    fn test_bfs() {
+        let graph = weighted();
+        let search = graph.bfs('a');
+
+        assert_eq!(search.distance('d'), Some(2));
+        assert_eq!(search.distance('f'), None);
+        assert_eq!(search.path_to('d').unwrap().nodes, vec!['a', 'b', 'd']);
+        assert_eq!(graph.bfs('e').len(), 1);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let mut graph = AdjacencyList::new();
+        graph.add_arc(1, 2, 1);
+        graph.add_arc(2, 3, 1);
+        graph.add_arc(1, 4, 1);
+
+        let order = graph.dfs(1);
+        assert_eq!(order[0], 1);
+        assert_eq!(order.len(), 4);
+        let (two, three) = (order.iter().position(|&n| n == 2), order.iter().position(|&n| n == 3));
+        assert_eq!(three.unwrap(), two.unwrap() + 1);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let graph = weighted();
+        let search = graph.dijkstra('a');
+
+        assert_eq!(search.distance('b'), Some(5));
+        assert_eq!(search.path_to('e'), Some(Path { cost: 10, nodes: vec!['a', 'c', 'b', 'd', 'e'] }));
+        assert_eq!(graph.dijkstra('e').path_to('a'), None);
+        assert_eq!(graph.shortest_path('a', |node| node == 'd').map(|path| path.cost), Some(6));
+    }
+
+    #[test]
+    fn test_all_shortest_paths() {
+        let mut graph = AdjacencyList::new();
+        graph.add_arc('a', 'b', 1);
+        graph.add_arc('a', 'c', 1);
+        graph.add_arc('b', 'd', 1);
+        graph.add_arc('c', 'd', 1);
+        graph.add_arc('a', 'd', 3);
+        graph.add_arc('d', 'e', 2);
+        graph.add_arc('d', 'f', 1);
+        graph.add_arc('f', 'e', 1);
+        graph.add_arc('a', 'x', 9);
+
+        let paths = graph.all_shortest_paths('a');
+        assert_eq!(paths.distance('e'), Some(4));
+        assert_eq!(paths.predecessors('d').len(), 2);
+        assert_eq!(paths.count_paths(['e']), 4);
+        assert_eq!(paths.count_paths(['d', 'x']), 3);
+        assert_eq!(paths.paths_to('d').len(), 2);
+        assert!(paths.paths_to('e').iter().all(|path| path.cost == 4 && path.nodes.len() >= 4));
+        assert_eq!(paths.covered(['d']), HashSet::from(['a', 'b', 'c', 'd']));
+        assert_eq!(paths.covered(['e']).len(), 6);
+        assert_eq!(paths.cheapest(['x', 'e', 'f', 'z']), vec!['f']);
+    }
+
+    #[test]
+    fn test_astar_on_implicit_grid() {
+        let maze = Grid::parse("..#.\n.##.\n....", "a tile", Some).unwrap();
+        let graph = from_fn(|position| {
+            maze.neighbours4(position).filter(|&next| maze[next] == '.').map(|next| (next, 1))
+        });
+        let goal = (0, 3);
+        let heuristic = |(row, column): (usize, usize)| (row.abs_diff(goal.0) + column.abs_diff(goal.1)) as u64;
+
+        let path = graph.astar((0, 0), |position| position == goal, heuristic).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&goal));
+        assert_eq!(graph.astar((0, 0), |position| position == (1, 1), heuristic), None);
+    }
+
+    #[test]
+    fn test_searches_with_dense_node_set() {
+        let maze = Grid::parse("..#.\n.##.\n....", "a tile", Some).unwrap();
+        let neighbours = |position| maze.neighbours4(position).filter(|&next| maze[next] == '.').map(|next| (next, 1));
+        let hashed = from_fn(neighbours);
+        let dense = with_node_set(from_fn(neighbours), || BitGrid::for_grid(&maze));
+
+        assert_eq!(dense.dfs((0, 0)), hashed.dfs((0, 0)));
+        assert_eq!(dense.dijkstra((0, 0)).distance((0, 3)), Some(7));
+        assert_eq!(dense.shortest_path((0, 0), |position| position == (0, 3)), hashed.shortest_path((0, 0), |position| position == (0, 3)));
+    }
+}
@@ -0,0 +1,335 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use crate::parse::ParseError;
+use crate::utils::geometry::{Direction, Point};
+
+/// A cell of a [`Grid`] as `(row, column)`.
+pub type Position = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+const SURROUNDING: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1)];
+
+/// A rectangular grid stored row by row in a single `Vec`.
+///
+/// Positions are `(row, column)` with `(0, 0)` in the top left corner. Everything that takes a
+/// position either checks it or says that it panics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// # Panics
+    /// When `cells` does not hold exactly `width * height` values.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "a {}x{} grid needs {} cells", width, height, width * height);
+        Grid { width, height, cells }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+
+    /// # Panics
+    /// When the rows do not all have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == width), "all rows of a grid need the same length");
+
+        let height = rows.len();
+        Grid { width, height, cells: rows.into_iter().flatten().collect() }
+    }
+
+    /// Parses one row per line, turning every character into a cell with `tile`. A character
+    /// `tile` rejects is reported as not being `expected`, and so is a line of the wrong length.
+    pub fn parse(input: &str, expected: &str, mut tile: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for line in input.lines() {
+            let row_start = cells.len();
+            for (index, character) in line.char_indices() {
+                let cell = tile(character)
+                    .ok_or_else(|| ParseError::at(input, &line[index..index + character.len_utf8()], expected))?;
+                cells.push(cell);
+            }
+
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(ParseError::at(input, line, format!("a row of {} cells", width)));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, column): Position) -> bool {
+        row < self.height && column < self.width
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position).then(|| &self.cells[self.index_of(position)])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if self.contains(position) {
+            let index = self.index_of(position);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// The position `step` away from `position`, if it is still on the grid.
+    pub fn offset(&self, position: Position, step: (isize, isize)) -> Option<Position> {
+        offset_within(position, step, self.width, self.height)
+    }
+
+    /// The position one step in `direction` from `position`, if it is still on the grid.
+    pub fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        let position = Point::from_position(position).step(direction).position()?;
+        self.contains(position).then_some(position)
+    }
+
+    /// The up to four positions sharing an edge with `position`, clockwise from the one above.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width, self.height);
+        ORTHOGONAL.into_iter().filter_map(move |step| offset_within(position, step, width, height))
+    }
+
+    /// The up to eight positions around `position`, clockwise from the top left one.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width, self.height);
+        SURROUNDING.into_iter().filter_map(move |step| offset_within(position, step, width, height))
+    }
+
+    /// `start` and every position reached by repeating `step` from it until leaving the grid.
+    pub fn ray(&self, start: Position, step: (isize, isize)) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width, self.height);
+        let start = self.contains(start).then_some(start);
+        std::iter::successors(start, move |&position| offset_within(position, step, width, height))
+    }
+
+    /// # Panics
+    /// When `row` is outside the grid.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.height, "row {} is outside the {}x{} grid", row, self.width, self.height);
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// The cells of `column` from top to bottom, nothing when it is outside the grid.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> + '_ {
+        let column = column.min(self.width);
+        (0..self.height).filter_map(move |row| self.get((row, column)))
+    }
+
+    /// The cells from `start` towards the bottom right corner.
+    pub fn diagonal(&self, start: Position) -> impl Iterator<Item = &T> + '_ {
+        self.ray(start, (1, 1)).map(|position| &self[position])
+    }
+
+    /// The cells from `start` towards the bottom left corner.
+    pub fn anti_diagonal(&self, start: Position) -> impl Iterator<Item = &T> + '_ {
+        self.ray(start, (1, -1)).map(|position| &self[position])
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    /// All cells, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// The first position, row by row, whose cell matches `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.cells.iter().position(predicate).map(|index| self.position_of(index))
+    }
+
+    pub fn find_all<'a>(&'a self, predicate: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = Position> + 'a {
+        self.iter().filter(move |(_, cell)| predicate(cell)).map(|(position, _)| position)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Mirrors the grid along its main diagonal, rows become columns.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rearranged(self.height, self.width, |(row, column)| (column, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.rearranged(self.height, self.width, |(row, column)| (height - 1 - column, row))
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.rearranged(self.height, self.width, |(row, column)| (column, width - 1 - row))
+    }
+
+    /// A `width` x `height` grid whose cell at each position is the cell of this grid at `source`
+    /// of that position.
+    fn rearranged(&self, width: usize, height: usize, source: impl Fn(Position) -> Position) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (row, column)))
+            .map(|position| self[source(position)].clone())
+            .collect();
+        Grid { width, height, cells }
+    }
+
+    fn index_of(&self, (row, column): Position) -> usize {
+        row * self.width + column
+    }
+
+    fn position_of(&self, index: usize) -> Position {
+        (index / self.width, index % self.width)
+    }
+}
+
+fn offset_within((row, column): Position, (row_step, column_step): (isize, isize), width: usize, height: usize) -> Option<Position> {
+    let row = row.checked_add_signed(row_step).filter(|&row| row < height)?;
+    let column = column.checked_add_signed(column_step).filter(|&column| column < width)?;
+    Some((row, column))
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        assert!(self.contains(position), "position {:?} is outside the {}x{} grid", position, self.width, self.height);
+        &self.cells[self.index_of(position)]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        assert!(self.contains(position), "position {:?} is outside the {}x{} grid", position, self.width, self.height);
+        let index = self.index_of(position);
+        &mut self.cells[index]
+    }
+}
+
+/// One line per row, the cells written next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid::*;
+    use std::assert_eq;
+
+    const SAMPLE: &str = "abc\ndef";
+
+    fn sample() -> Grid<char> {
+        Grid::parse(SAMPLE, "a letter", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.to_string(), SAMPLE);
+
+        let error = Grid::parse("#.\n.x", "`#` or `.`", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 2, "`#` or `.`"));
+
+        let error = Grid::parse("#.\n.", "`#` or `.`", Some).unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (2, "a row of 2 cells"));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = sample();
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours4((1, 1)).collect::<Vec<_>>(), vec![(0, 1), (1, 2), (1, 0)]);
+        assert_eq!(grid.neighbours8((1, 1)).collect::<Vec<_>>(), vec![(0, 0), (0, 1), (0, 2), (1, 2), (1, 0)]);
+        assert_eq!(grid.offset((1, 2), (-1, -2)), Some((0, 0)));
+        assert_eq!(grid.offset((1, 2), (0, 1)), None);
+        assert_eq!(grid.step((1, 2), Direction::Left), Some((1, 1)));
+        assert_eq!(grid.step((0, 2), Direction::Up), None);
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = sample();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.diagonal((0, 0)).collect::<String>(), "ae");
+        assert_eq!(grid.anti_diagonal((0, 2)).collect::<String>(), "ce");
+        assert_eq!(grid.ray((1, 2), (0, -1)).collect::<Vec<_>>(), vec![(1, 2), (1, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Grid::parse("#.#\n..#", "", Some).unwrap();
+        assert_eq!(grid.find(|&c| c == '.'), Some((0, 1)));
+        assert_eq!(grid.find_all(|&c| c == '#').collect::<Vec<_>>(), vec![(0, 0), (0, 2), (1, 2)]);
+        assert_eq!(grid.find(|&c| c == 'S'), None);
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+}
@@ -0,0 +1,235 @@
+//! Small parsers for the shapes puzzle inputs keep coming in.
+//!
+//! Every parser takes the whole `input` next to the slice of it being parsed, so the
+//! [`ParseError`]s they return point at the right line and column.
+
+use std::ops::Range;
+use std::str::FromStr;
+use crate::parse::{self, ParseError};
+
+/// Byte ranges of the runs of non-blank lines in `text`, without their line breaks.
+fn block_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut current: Option<Range<usize>> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            ranges.extend(current.take());
+        } else {
+            current.get_or_insert(offset..offset).end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    ranges.extend(current);
+    ranges
+}
+
+/// The blocks of `text` separated by blank lines, blank lines around them left out.
+pub fn blocks(text: &str) -> Vec<&str> {
+    block_ranges(text).into_iter().map(|range| &text[range]).collect()
+}
+
+/// `input` split at its first blank line into the first block and everything after it; an
+/// error naming `second`, e.g. `"the moves"`, when there is no second block.
+pub fn two_blocks<'a>(input: &'a str, second: &str) -> Result<(&'a str, &'a str), ParseError> {
+    let ranges = block_ranges(input);
+    match ranges.as_slice() {
+        [head, next, rest @ ..] => {
+            let end = rest.last().unwrap_or(next).end;
+            Ok((&input[head.clone()], &input[next.start..end]))
+        }
+        _ => Err(ParseError::end(input, format!("a blank line before {}", second))),
+    }
+}
+
+/// Every integer in `text`, a slice of `input`, in order. A `-` right before the digits makes
+/// the number negative; everything else between the numbers is skipped.
+pub fn signed_ints<T: FromStr>(input: &str, text: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let negative = bytes[index] == b'-' && bytes.get(index + 1).is_some_and(u8::is_ascii_digit);
+        if !negative && !bytes[index].is_ascii_digit() {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        index += 1;
+        while bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            index += 1;
+        }
+        numbers.push(parse::number(input, &text[start..index])?);
+    }
+
+    Ok(numbers)
+}
+
+/// Exactly `N` integers out of `text`, see [`signed_ints`]; `expected` describes the line
+/// when the count is off.
+pub fn signed_ints_n<T: FromStr, const N: usize>(input: &str, text: &str, expected: &str) -> Result<[T; N], ParseError> {
+    signed_ints(input, text)?.try_into().map_err(|_| ParseError::at(input, text, expected))
+}
+
+/// `text` split around the first `separator`, both sides trimmed.
+pub fn separated<'a>(input: &str, text: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseError> {
+    let (left, right) = text.split_once(separator).ok_or_else(|| ParseError::after(input, text, format!("`{}`", separator)))?;
+    Ok((left.trim(), right.trim()))
+}
+
+/// The `a-b` lines of `text` as pairs of names, blank lines skipped.
+pub fn edges<'a>(input: &str, text: &'a str) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match separated(input, line, "-")? {
+            ("", _) | (_, "") => Err(ParseError::at(input, line, "`<name>-<name>`")),
+            edge => Ok(edge),
+        })
+        .collect()
+}
+
+/// The `key: value` lines of `text`, blank lines skipped, with every value turned into a `T` by
+/// `value`; `expected` describes the values it accepts.
+pub fn key_values<'a, T>(
+    input: &str,
+    text: &'a str,
+    expected: &str,
+    mut value: impl FnMut(&'a str) -> Option<T>,
+) -> Result<Vec<(&'a str, T)>, ParseError> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (key, raw) = separated(input, line, ":")?;
+            if key.is_empty() {
+                return Err(ParseError::at(input, line, "`<key>: <value>`"));
+            }
+            let parsed = value(raw).ok_or_else(|| ParseError::at(input, raw, expected))?;
+            Ok((key, parsed))
+        })
+        .collect()
+}
+
+/// Settings at the start of an input, one `@<name> <value>` line each.
+///
+/// Some puzzles fix a number for the real input and change it for their example, such as the
+/// size of a grid. Example inputs give it as a setting; real inputs have none and get the
+/// puzzle's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings<'a> {
+    input: &'a str,
+    values: Vec<(&'a str, &'a str)>,
+}
+
+impl Settings<'_> {
+    /// The value of `name` as a number, `default` when it is not set.
+    pub fn number<T: FromStr>(&self, name: &str, default: T) -> Result<T, ParseError> {
+        match self.values.iter().rev().find(|(known, _)| *known == name) {
+            Some((_, value)) => parse::number(self.input, value),
+            None => Ok(default),
+        }
+    }
+}
+
+/// The settings lines at the start of `input`, allowing the names in `known` only, and the
+/// input after them.
+pub fn settings<'a>(input: &'a str, known: &[&str]) -> Result<(Settings<'a>, &'a str), ParseError> {
+    let mut values = Vec::new();
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let Some(setting) = line.trim_end().strip_prefix('@') else {
+            break;
+        };
+        let (name, value) = setting.split_once(' ').ok_or_else(|| ParseError::at(input, line.trim_end(), "`@<name> <value>`"))?;
+        if !known.contains(&name) {
+            let names: Vec<String> = known.iter().map(|known| format!("`{}`", known)).collect();
+            return Err(ParseError::at(input, name, format!("a setting, one of {}", names.join(", "))));
+        }
+        values.push((name, value.trim()));
+        offset += line.len();
+    }
+
+    Ok((Settings { input, values }, &input[offset..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::parsing::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_blocks() {
+        let input = "\n#..\n.#.\n\n\n  \nabc\r\n\n9\n";
+
+        assert_eq!(blocks(input), vec!["#..\n.#.", "abc", "9"]);
+        assert_eq!(two_blocks(input, "the rest"), Ok(("#..\n.#.", "abc\r\n\n9")));
+        assert_eq!(two_blocks("47|53\n97|13", "the updates").unwrap_err().expected, "a blank line before the updates");
+        assert!(blocks("\n \n").is_empty());
+    }
+
+    #[test]
+    fn test_signed_ints() {
+        let input = "p=0,4 v=3,-3\nButton A: X+94, Y-34\nx=1,y=99999999999";
+        let lines: Vec<&str> = input.lines().collect();
+
+        assert_eq!(signed_ints::<i64>(input, lines[0]), Ok(vec![0, 4, 3, -3]));
+        assert_eq!(signed_ints_n::<f64, 2>(input, lines[1], "two numbers"), Ok([94.0, -34.0]));
+        assert_eq!(signed_ints::<i32>(input, "- -"), Ok(vec![]));
+
+        let error = signed_ints_n::<i64, 3>(input, lines[0], "three numbers").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (1, 1, "three numbers"));
+        let error = signed_ints::<i32>(input, lines[2]).unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (3, 7, "99999999999"));
+    }
+
+    #[test]
+    fn test_edges() {
+        let input = "kh-tc\n qp-kh \n\nde-\nab";
+        let lines: Vec<&str> = input.lines().collect();
+
+        assert_eq!(edges(input, &input[..13]), Ok(vec![("kh", "tc"), ("qp", "kh")]));
+        assert_eq!(edges(input, lines[3]).unwrap_err().expected, "`<name>-<name>`");
+        assert_eq!(edges(input, lines[4]).unwrap_err().expected, "`-`");
+    }
+
+    #[test]
+    fn test_key_values() {
+        let input = "x00: 1\ny01: 0\n\nz02: 7\n: 1";
+        let bit = |value| match value {
+            "0" => Some(false),
+            "1" => Some(true),
+            _ => None,
+        };
+
+        assert_eq!(key_values(input, &input[..13], "a bit", bit), Ok(vec![("x00", true), ("y01", false)]));
+
+        let error = key_values(input, input, "a bit", bit).unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (4, 6, "a bit"));
+        let error = key_values(input, input.lines().nth(4).unwrap(), "a number", |value| value.parse::<u8>().ok()).unwrap_err();
+        assert_eq!(error.expected, "`<key>: <value>`");
+    }
+
+    #[test]
+    fn test_settings() {
+        let input = "@width 11\n@height 7\np=0,4 v=3,-3";
+
+        let (values, rest) = settings(input, &["width", "height"]).unwrap();
+        assert_eq!(rest, "p=0,4 v=3,-3");
+        assert_eq!(values.number("width", 101), Ok(11));
+        assert_eq!(values.number("depth", 5), Ok(5));
+
+        let (values, rest) = settings("5,4\n4,2", &["size"]).unwrap();
+        assert_eq!((values.number("size", 71), rest), (Ok(71), "5,4\n4,2"));
+
+        let error = settings(input, &["size"]).unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (1, 2, "a setting, one of `size`"));
+        assert_eq!(settings("@size\n", &["size"]).unwrap_err().expected, "`@<name> <value>`");
+        let (values, _) = settings("@size seven", &["size"]).unwrap();
+        assert_eq!(values.number::<usize>("size", 71).unwrap_err().found, "seven");
+    }
+}
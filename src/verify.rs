@@ -0,0 +1,104 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::answer::Answer;
+
+/// Expected answers for both parts of one puzzle input, `None` when a part is not known yet.
+///
+/// They are stored next to the input, `./data/day07.txt` is checked against
+/// `./data/day07.answers`, with one `partN: <answer>` line per known part:
+///
+/// ```text
+/// part1: 3749
+/// part2: 11387
+/// ```
+pub type ExpectedAnswers = [Option<Answer>; 2];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing,
+    Panicked,
+    TimedOut,
+    Invalid,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Missing => "MISSING",
+            Status::Panicked => "PANICKED",
+            Status::TimedOut => "TIMEOUT",
+            Status::Invalid => "INVALID",
+        };
+        f.pad(label)
+    }
+}
+
+pub fn answers_path(input_path: &Path) -> PathBuf {
+    input_path.with_extension("answers")
+}
+
+/// Reads the answers stored for `input_path`; a missing file means no part is known.
+pub fn load_expected(input_path: &Path) -> ExpectedAnswers {
+    fs::read_to_string(answers_path(input_path))
+        .map(|content| parse_expected(&content))
+        .unwrap_or_default()
+}
+
+pub fn parse_expected(content: &str) -> ExpectedAnswers {
+    let mut expected: ExpectedAnswers = [None, None];
+
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let index = match key.trim() {
+            "part1" => 0,
+            "part2" => 1,
+            _ => continue,
+        };
+        if !value.trim().is_empty() {
+            expected[index] = Some(value.parse().unwrap());
+        }
+    }
+
+    expected
+}
+
+pub fn check(answer: &Answer, expected: Option<&Answer>) -> Status {
+    match expected {
+        Some(expected) if expected == answer => Status::Pass,
+        Some(_) => Status::Fail,
+        None => Status::Missing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::verify::*;
+    use std::assert_eq;
+
+    #[test]
+    fn test_parse_expected() {
+        let expected = parse_expected("# day 17\npart1: 4,6,3,5,6,3,5,2,1,0\n\npart2: 117440\n");
+
+        assert_eq!(expected, [Some(Answer::from("4,6,3,5,6,3,5,2,1,0")), Some(Answer::Integer(117440))]);
+    }
+
+    #[test]
+    fn test_parse_expected_with_missing_part() {
+        assert_eq!(parse_expected("part2: 31\npart3: 1"), [None, Some(Answer::Integer(31))]);
+        assert_eq!(parse_expected("part1:\n"), [None, None]);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(&Answer::from(11), Some(&Answer::from(11))), Status::Pass);
+        assert_eq!(check(&Answer::from(11), Some(&Answer::from(12))), Status::Fail);
+        assert_eq!(check(&Answer::from(11), None), Status::Missing);
+    }
+}
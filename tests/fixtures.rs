@@ -0,0 +1,56 @@
+//! Runs every example input in `tests/fixtures` through its day and compares the answers with
+//! the `.answers` file next to it.
+//!
+//! A fixture is a `dayNN.<name>.txt` input plus a `dayNN.<name>.answers` file in the format of
+//! `aoc2024 verify`. Parts without an expected answer are not run, so a fixture may cover just
+//! one part. Adding a regression case is just dropping in those two files.
+//!
+//! Days whose example uses smaller numbers than the real puzzle, like a grid size, read them
+//! from `@<name> <value>` lines at the top of the input.
+
+use std::path::Path;
+use aoc2024::input::{self, InputSource};
+use aoc2024::solution::Part;
+use aoc2024::verify::{self, Status};
+
+#[test]
+fn test_fixtures() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut checked = 0;
+    let mut failures = Vec::new();
+
+    for solution in aoc2024::SOLUTIONS {
+        let day = solution.day();
+        for name in input::named_inputs(&fixtures, day).into_iter().flatten() {
+            let source = InputSource::resolve(day, None, &fixtures, Some(&name));
+            let input = source.read().unwrap();
+            let expected = source.expected();
+            let parts: Vec<Part> = Part::BOTH.into_iter().filter(|part| expected[part.index()].is_some()).collect();
+            assert!(!parts.is_empty(), "{} has no expected answers", source);
+
+            let run = match solution.run(&input, &parts) {
+                Ok(run) => run,
+                Err(error) => {
+                    failures.push(format!("{}: {}\n{}", source, error, error.excerpt()));
+                    continue;
+                }
+            };
+            for part_run in run.parts {
+                let expected = expected[part_run.part.index()].as_ref();
+                if verify::check(&part_run.answer, expected) != Status::Pass {
+                    failures.push(format!(
+                        "{} part {}: got {}, expected {}",
+                        source,
+                        part_run.part,
+                        part_run.answer,
+                        expected.unwrap()
+                    ));
+                }
+                checked += 1;
+            }
+        }
+    }
+
+    assert!(checked > 0, "no fixtures found in {}", fixtures.display());
+    assert!(failures.is_empty(), "{} of {} fixture answers are wrong:\n{}", failures.len(), checked, failures.join("\n"));
+}